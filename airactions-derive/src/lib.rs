@@ -0,0 +1,343 @@
+//! `#[derive(ApiAction)]` for actions, which send request as json
+//! (or as query string for `GET`) and receive json response.
+//!
+//! Container attributes:
+//! - `request = Type` and `response = Type` - required.
+//! - `path = "..."` - required for structs, for enums every variant
+//!   declares its own `#[action(path = "...")]`. `{name}` placeholders
+//!   are filled with the `name` field of the serialized request.
+//! - `method = "GET"` - http method, `POST` by default.
+//! - `header("X-Name", "value")` - header, added to every request,
+//!   can be repeated. Name and value are checked at compile time.
+//! - `idempotent` - sets `ApiAction::IDEMPOTENT` to `true`.
+//! - `idempotency_key = "field"` - `ApiAction::idempotency_key` is taken
+//!   from the `field` of the serialized request (the name after serde
+//!   renames). Fields are not visible to the macro, so it can't check
+//!   the name: if the serialized request has no such scalar field,
+//!   the key is `None` and requests are not deduplicated.
+//! - `cache_ttl_secs = 30` - `ApiAction::cache_ttl` in seconds.
+//!
+//! ```ignore
+//! #[derive(ApiAction)]
+//! #[action(path = "/token/info", request = TokenInfoRequest, response = TokenInfoResponse)]
+//! pub struct TokenInfo;
+//! ```
+
+use http::header::{HeaderName, HeaderValue};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, LitInt, LitStr, Token,
+    Type,
+};
+
+/// Methods, which have constants in `airactions::Method`.
+const METHODS: &[&str] = &[
+    "GET", "POST", "PUT", "DELETE", "HEAD", "OPTIONS", "CONNECT", "PATCH",
+    "TRACE",
+];
+
+#[proc_macro_derive(ApiAction, attributes(action))]
+pub fn derive_api_action(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct ActionAttrs {
+    path: Option<LitStr>,
+    method: Option<LitStr>,
+    request: Option<Type>,
+    response: Option<Type>,
+    headers: Vec<(LitStr, LitStr)>,
+    idempotent: bool,
+    idempotency_key: Option<LitStr>,
+    cache_ttl_secs: Option<LitInt>,
+}
+
+impl ActionAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = ActionAttrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("action")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("path") {
+                    result.path = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("method") {
+                    let method: LitStr = meta.value()?.parse()?;
+                    if !METHODS
+                        .contains(&method.value().to_uppercase().as_str())
+                    {
+                        return Err(syn::Error::new_spanned(
+                            method,
+                            format!("method should be one of {METHODS:?}"),
+                        ));
+                    }
+                    result.method = Some(method);
+                } else if meta.path.is_ident("request") {
+                    result.request = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("response") {
+                    result.response = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("header") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let name: LitStr = content.parse()?;
+                    content.parse::<Token![,]>()?;
+                    let value: LitStr = content.parse()?;
+                    let lowercase = name.value().to_lowercase();
+                    if HeaderName::from_bytes(lowercase.as_bytes()).is_err() {
+                        return Err(syn::Error::new_spanned(
+                            name,
+                            "invalid header name",
+                        ));
+                    }
+                    // `from_static` accepts only visible ASCII,
+                    // `from_str` also accepts other non-control bytes.
+                    let valid = value.value().is_ascii()
+                        && HeaderValue::from_str(&value.value()).is_ok();
+                    if !valid {
+                        return Err(syn::Error::new_spanned(
+                            value,
+                            "invalid header value, only visible ASCII \
+                             characters are allowed",
+                        ));
+                    }
+                    result.headers.push((name, value));
+                } else if meta.path.is_ident("idempotent") {
+                    result.idempotent = true;
+                } else if meta.path.is_ident("idempotency_key") {
+                    result.idempotency_key = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("cache_ttl_secs") {
+                    result.cache_ttl_secs = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unsupported action attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let attrs = ActionAttrs::parse(&input.attrs)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    let request = attrs.request.ok_or_else(|| {
+        syn::Error::new_spanned(name, "missing `#[action(request = ...)]`")
+    })?;
+    let response = attrs.response.ok_or_else(|| {
+        syn::Error::new_spanned(name, "missing `#[action(response = ...)]`")
+    })?;
+    let method = match attrs.method {
+        Some(method) => {
+            let ident =
+                syn::Ident::new(&method.value().to_uppercase(), method.span());
+            quote!(::airactions::Method::#ident)
+        }
+        None => quote!(::airactions::Method::POST),
+    };
+    let idempotent = attrs.idempotent.then(|| {
+        quote!(
+            const IDEMPOTENT: bool = true;
+        )
+    });
+    let idempotency_key = attrs.idempotency_key.map(|field| {
+        quote! {
+            fn idempotency_key(
+                &self,
+                req: &Self::Request,
+            ) -> ::std::option::Option<::std::string::String> {
+                ::airactions::__private::path_params(req, &[#field])
+                    .pop()
+                    .map(|(_, key)| key)
+            }
+        }
+    });
+    let cache_ttl = attrs.cache_ttl_secs.map(|secs| {
+        quote! {
+            fn cache_ttl(
+                &self,
+            ) -> ::std::option::Option<::std::time::Duration> {
+                ::std::option::Option::Some(
+                    ::std::time::Duration::from_secs(#secs),
+                )
+            }
+        }
+    });
+    let headers = (!attrs.headers.is_empty()).then(|| {
+        let inserts = attrs.headers.iter().map(|(name, value)| {
+            let name = LitStr::new(&name.value().to_lowercase(), name.span());
+            quote! {
+                headers.insert(
+                    ::airactions::header::HeaderName::from_static(#name),
+                    ::airactions::header::HeaderValue::from_static(#value),
+                );
+            }
+        });
+        quote! {
+            fn headers(&self) -> ::airactions::header::HeaderMap {
+                let mut headers = ::airactions::header::HeaderMap::new();
+                #(#inserts)*
+                headers
+            }
+        }
+    });
+
+    let (url_path, params) = match input.data {
+        Data::Struct(_) => match attrs.path {
+            Some(path) => {
+                let params = placeholders(&path);
+                (quote!(#path), quote!(&[#(#params),*]))
+            }
+            None => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "missing `#[action(path = \"...\")]`",
+                ))
+            }
+        },
+        Data::Enum(ref data) => {
+            if let Some(path) = attrs.path {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "enums declare `path` on every variant",
+                ));
+            }
+            let mut arms = Vec::new();
+            let mut param_arms = Vec::new();
+            for variant in data.variants.iter() {
+                let ident = &variant.ident;
+                let path = ActionAttrs::parse(&variant.attrs)?
+                    .path
+                    .ok_or_else(|| {
+                        syn::Error::new_spanned(
+                            ident,
+                            "missing `#[action(path = \"...\")]`",
+                        )
+                    })?;
+                let params = placeholders(&path);
+                arms.push(quote!(Self::#ident { .. } => #path,));
+                param_arms
+                    .push(quote!(Self::#ident { .. } => &[#(#params),*],));
+            }
+            (
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                },
+                quote! {
+                    match self {
+                        #(#param_arms)*
+                    }
+                },
+            )
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "ApiAction can't be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::airactions::ApiAction for #name #ty_generics
+            #where_clause
+        {
+            type Request = #request;
+            type Response = #response;
+            #idempotent
+            fn url_path(&self) -> &'static str {
+                #url_path
+            }
+            fn method(&self) -> ::airactions::Method {
+                #method
+            }
+            #headers
+            #idempotency_key
+            #cache_ttl
+            fn path_params(
+                &self,
+                req: &Self::Request,
+            ) -> ::std::vec::Vec<(&'static str, ::std::string::String)> {
+                let names: &[&'static str] = #params;
+                if names.is_empty() {
+                    return ::std::vec::Vec::new();
+                }
+                ::airactions::__private::path_params(req, names)
+            }
+            async fn perform_action(
+                req: Self::Request,
+                addr: ::airactions::Url,
+                client: &::airactions::HttpClient<'_>,
+            ) -> ::std::result::Result<
+                Self::Response,
+                ::airactions::ClientError,
+            > {
+                client.send_json(#method, addr, &req).await
+            }
+        }
+    })
+}
+
+/// Names of `{name}` placeholders in the path template.
+fn placeholders(path: &LitStr) -> Vec<String> {
+    let path = path.value();
+    let mut names = Vec::new();
+    let mut rest = path.as_str();
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        names.push(rest[start + 1..start + end].to_string());
+        rest = &rest[start + end + 1..];
+    }
+    names
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::expand;
+
+    fn error(input: syn::DeriveInput) -> String {
+        expand(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn unknown_method_is_compile_error() {
+        let message = error(parse_quote! {
+            #[action(path = "/a", method = "FETCH-ALL", request = A, response = B)]
+            struct Action;
+        });
+        assert!(message.starts_with("method should be one of"));
+    }
+
+    #[test]
+    fn invalid_headers_are_compile_errors() {
+        let message = error(parse_quote! {
+            #[action(path = "/a", header("X Api", "1"), request = A, response = B)]
+            struct Action;
+        });
+        assert_eq!(message, "invalid header name");
+        let message = error(parse_quote! {
+            #[action(path = "/a", header("X-Api", "ключ"), request = A, response = B)]
+            struct Action;
+        });
+        assert!(message.starts_with("invalid header value"));
+        assert!(expand(parse_quote! {
+            #[action(path = "/a", method = "put", header("X-Api", "2"), request = A, response = B)]
+            struct Action;
+        })
+        .is_ok());
+    }
+}
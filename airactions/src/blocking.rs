@@ -0,0 +1,121 @@
+//! Synchronous facade over the `Client`, enabled with the `blocking`
+//! feature.
+//!
+//! `BlockingClient` owns a single-threaded tokio runtime and drives
+//! executed actions on it, so it can be used from plain threads. It must
+//! not be used from async code, `Runtime::block_on` panics there.
+//! ```rust
+//! use airactions::transport::{HttpResponse, InMemoryTransport};
+//! use airactions::{ApiAction, BlockingClient, ClientError, HttpClient};
+//! use airactions::{Method, StatusCode, Url};
+//!
+//! struct Ping;
+//!
+//! impl ApiAction for Ping {
+//!     type Request = ();
+//!     type Response = String;
+//!     fn url_path(&self) -> &'static str {
+//!         "/ping"
+//!     }
+//!     async fn perform_action(
+//!         _req: Self::Request,
+//!         addr: Url,
+//!         client: &HttpClient<'_>,
+//!     ) -> Result<Self::Response, ClientError> {
+//!         Ok(client.get(addr).send().await?.text())
+//!     }
+//! }
+//!
+//! let transport = InMemoryTransport::new().route(
+//!     Method::GET,
+//!     "/ping",
+//!     |_req| HttpResponse::new(StatusCode::OK, "pong"),
+//! );
+//! let client =
+//!     BlockingClient::with_transport("https://happydog.org", transport)
+//!         .unwrap();
+//! assert_eq!(client.execute(Ping, ()).unwrap(), "pong");
+//! ```
+
+use std::sync::Arc;
+
+use reqwest::IntoUrl;
+use tokio::runtime::Runtime;
+
+use crate::transport::{ReqwestTransport, Transport};
+use crate::{ApiAction, Client, ClientError, ExecuteOptions};
+
+pub struct BlockingClient<T = ReqwestTransport> {
+    inner: Client<T>,
+    runtime: Arc<Runtime>,
+}
+
+impl BlockingClient {
+    /// Client with default timeouts, see `ClientBuilder`.
+    pub fn new(url: impl IntoUrl) -> Result<Self, ClientError> {
+        BlockingClient::from_async(Client::new(url)?)
+    }
+}
+
+impl<T: Transport> BlockingClient<T> {
+    pub fn with_transport(
+        url: impl IntoUrl,
+        transport: T,
+    ) -> Result<Self, ClientError> {
+        BlockingClient::from_async(Client::with_transport(url, transport)?)
+    }
+
+    /// Wrap configured async client, e.g. built with `ClientBuilder`.
+    pub fn from_async(client: Client<T>) -> Result<Self, ClientError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(ClientError::RuntimeError)?;
+        Ok(BlockingClient {
+            inner: client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Underlying async client.
+    pub fn client(&self) -> &Client<T> {
+        &self.inner
+    }
+
+    /// See `Client::execute`.
+    pub fn execute<A: ApiAction>(
+        &self,
+        action: A,
+        data: A::Request,
+    ) -> Result<A::Response, ClientError> {
+        self.runtime.block_on(self.inner.execute(action, data))
+    }
+
+    /// See `Client::execute_with`.
+    pub fn execute_with<A: ApiAction>(
+        &self,
+        action: A,
+        data: A::Request,
+        options: ExecuteOptions,
+    ) -> Result<A::Response, ClientError> {
+        self.runtime
+            .block_on(self.inner.execute_with(action, data, options))
+    }
+}
+
+impl<T> Clone for BlockingClient<T> {
+    fn clone(&self) -> Self {
+        BlockingClient {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for BlockingClient<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockingClient")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
@@ -0,0 +1,391 @@
+//! Circuit breaker, which stops sending requests to the failing upstream.
+//!
+//! After `failure_threshold` consecutive failures the circuit opens and
+//! every request is rejected with `ClientError::CircuitOpen` without
+//! touching the network. After the cool-down the circuit becomes
+//! half-open and lets a trial request through: its success closes
+//! the circuit, its failure opens it again.
+//!
+//! Failures are transport errors, timeouts, `429` and `5xx` responses.
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use airactions::breaker::{CircuitBreaker, CircuitState};
+//! use airactions::Client;
+//!
+//! let breaker = CircuitBreaker::new(5, Duration::from_secs(30))
+//!     .on_state_change(|state, ctx| {
+//!         if state == CircuitState::Open {
+//!             println!("{} is down, enable degraded mode", ctx.url_path);
+//!         }
+//!     });
+//! let client = Client::new("https://happydog.org")
+//!     .unwrap()
+//!     .with_middleware(breaker);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+
+use crate::middleware::{ActionContext, BoxFuture, Middleware, Next};
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::ClientError;
+
+/// Key of the circuit, shared by all actions.
+const SHARED: &str = "";
+
+type StateCallback = Box<dyn Fn(CircuitState, &ActionContext) + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent, failures are counted.
+    Closed,
+    /// Requests are rejected until the cool-down ends.
+    Open,
+    /// Limited number of trial requests is sent.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum Circuit {
+    Closed { failures: u32 },
+    Open { until: Option<Instant> },
+    HalfOpen { trials: u32 },
+}
+
+impl Circuit {
+    fn state(&self) -> CircuitState {
+        match self {
+            Circuit::Closed { .. } => CircuitState::Closed,
+            Circuit::Open { .. } => CircuitState::Open,
+            Circuit::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+}
+
+enum Outcome {
+    Success,
+    Failure,
+    /// Request was dropped before the response.
+    Abandoned,
+}
+
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cool_down: Duration,
+    half_open_trials: u32,
+    per_path: bool,
+    circuits: Mutex<HashMap<&'static str, Circuit>>,
+    on_state_change: Option<StateCallback>,
+}
+
+impl CircuitBreaker {
+    /// Open the circuit after `failure_threshold` consecutive failures
+    /// and keep it open for `cool_down`.
+    pub fn new(failure_threshold: u32, cool_down: Duration) -> Self {
+        CircuitBreaker {
+            failure_threshold: failure_threshold.max(1),
+            cool_down,
+            half_open_trials: 1,
+            per_path: false,
+            circuits: Mutex::new(HashMap::new()),
+            on_state_change: None,
+        }
+    }
+    /// Number of concurrent trial requests in the half-open state,
+    /// `1` by default.
+    pub fn with_half_open_trials(mut self, trials: u32) -> Self {
+        self.half_open_trials = trials.max(1);
+        self
+    }
+    /// Keep separate circuit for every `ApiAction::url_path`.
+    pub fn per_path(mut self) -> Self {
+        self.per_path = true;
+        self
+    }
+    /// Call the closure every time the circuit changes its state.
+    pub fn on_state_change<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(CircuitState, &ActionContext) + Send + Sync + 'static,
+    {
+        self.on_state_change = Some(Box::new(callback));
+        self
+    }
+
+    /// State of the shared circuit, or of the circuit of the given path
+    /// with `per_path`.
+    pub fn state(&self, url_path: &str) -> CircuitState {
+        let key = if self.per_path { url_path } else { SHARED };
+        self.circuits
+            .lock()
+            .unwrap()
+            .get(key)
+            .map(Circuit::state)
+            .unwrap_or(CircuitState::Closed)
+    }
+
+    fn key(&self, ctx: &ActionContext) -> &'static str {
+        if self.per_path {
+            ctx.url_path
+        } else {
+            SHARED
+        }
+    }
+
+    /// Check if the request is allowed and take a trial slot
+    /// in the half-open state.
+    fn acquire(&self, ctx: &ActionContext) -> Result<(), ClientError> {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits
+            .entry(self.key(ctx))
+            .or_insert(Circuit::Closed { failures: 0 });
+        match circuit {
+            Circuit::Closed { .. } => Ok(()),
+            Circuit::Open { until }
+                if until.is_none_or(|until| Instant::now() < until) =>
+            {
+                Err(ClientError::CircuitOpen)
+            }
+            Circuit::Open { .. } => {
+                *circuit = Circuit::HalfOpen { trials: 1 };
+                drop(circuits);
+                self.notify(CircuitState::HalfOpen, ctx);
+                Ok(())
+            }
+            Circuit::HalfOpen { trials }
+                if *trials >= self.half_open_trials =>
+            {
+                Err(ClientError::CircuitOpen)
+            }
+            Circuit::HalfOpen { trials } => {
+                *trials += 1;
+                Ok(())
+            }
+        }
+    }
+
+    fn record(&self, ctx: &ActionContext, outcome: Outcome) {
+        let mut circuits = self.circuits.lock().unwrap();
+        let Some(circuit) = circuits.get_mut(self.key(ctx)) else {
+            return;
+        };
+        let before = circuit.state();
+        match (&mut *circuit, outcome) {
+            (Circuit::Closed { failures }, Outcome::Success) => *failures = 0,
+            (Circuit::Closed { failures }, Outcome::Failure) => {
+                *failures += 1;
+                if *failures >= self.failure_threshold {
+                    *circuit = self.open();
+                }
+            }
+            (Circuit::HalfOpen { .. }, Outcome::Success) => {
+                *circuit = Circuit::Closed { failures: 0 }
+            }
+            (Circuit::HalfOpen { .. }, Outcome::Failure) => {
+                *circuit = self.open()
+            }
+            (Circuit::HalfOpen { trials }, Outcome::Abandoned) => {
+                *trials = trials.saturating_sub(1)
+            }
+            _ => {}
+        }
+        let after = circuit.state();
+        drop(circuits);
+        if before != after {
+            self.notify(after, ctx);
+        }
+    }
+
+    /// Circuit stays open indefinitely, if the cool-down does not fit
+    /// into `Instant`.
+    fn open(&self) -> Circuit {
+        Circuit::Open {
+            until: Instant::now().checked_add(self.cool_down),
+        }
+    }
+
+    fn notify(&self, state: CircuitState, ctx: &ActionContext) {
+        tracing::warn!(?state, url_path = ctx.url_path, "Circuit changed");
+        if let Some(ref callback) = self.on_state_change {
+            callback(state, ctx);
+        }
+    }
+}
+
+impl Middleware for CircuitBreaker {
+    fn handle<'a>(
+        &'a self,
+        req: HttpRequest,
+        ctx: &'a ActionContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            self.acquire(ctx)?;
+            let mut guard = Guard {
+                breaker: self,
+                ctx,
+                recorded: false,
+            };
+            let result = next.run(req, ctx).await;
+            let outcome = match result {
+                Ok(ref response) if is_failure_status(response.status) => {
+                    Outcome::Failure
+                }
+                Ok(_) => Outcome::Success,
+                Err(
+                    ClientError::ReqwestError(_)
+                    | ClientError::Timeout
+                    | ClientError::StatusError(_),
+                ) => Outcome::Failure,
+                Err(_) => Outcome::Abandoned,
+            };
+            guard.recorded = true;
+            self.record(ctx, outcome);
+            result
+        })
+    }
+}
+
+impl std::fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("failure_threshold", &self.failure_threshold)
+            .field("cool_down", &self.cool_down)
+            .field("half_open_trials", &self.half_open_trials)
+            .field("per_path", &self.per_path)
+            .field("circuits", &self.circuits)
+            .finish()
+    }
+}
+
+/// Releases the trial slot, if the request is dropped before the response.
+struct Guard<'a> {
+    breaker: &'a CircuitBreaker,
+    ctx: &'a ActionContext,
+    recorded: bool,
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.breaker.record(self.ctx, Outcome::Abandoned);
+        }
+    }
+}
+
+fn is_failure_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use url::Url;
+
+    use super::{CircuitBreaker, CircuitState};
+    use crate::transport::{HttpResponse, InMemoryTransport};
+    use crate::{ApiAction, Client, ClientError, HttpClient};
+    use crate::{Method, StatusCode};
+
+    struct Ping;
+
+    impl ApiAction for Ping {
+        type Request = ();
+        type Response = ();
+        fn url_path(&self) -> &'static str {
+            "/ping"
+        }
+        async fn perform_action(
+            _req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client.get(addr).send().await?.error_for_status()?;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn circuit_opens_after_failures_and_recovers_after_cool_down() {
+        let status = Arc::new(Mutex::new(StatusCode::SERVICE_UNAVAILABLE));
+        let transport = InMemoryTransport::new().route(Method::GET, "/ping", {
+            let status = status.clone();
+            move |_| HttpResponse::new(*status.lock().unwrap(), Vec::new())
+        });
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(50))
+            .on_state_change({
+                let changes = changes.clone();
+                move |state, _ctx| changes.lock().unwrap().push(state)
+            });
+        let client =
+            Client::with_transport("https://happydog.org", transport.clone())
+                .unwrap()
+                .with_middleware(breaker);
+
+        for _ in 0..2 {
+            let response = client.execute(Ping, ()).await;
+            assert!(matches!(response, Err(ClientError::StatusError(_))));
+        }
+        let response = client.execute(Ping, ()).await;
+        assert!(matches!(response, Err(ClientError::CircuitOpen)));
+        assert_eq!(transport.requests().len(), 2);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        *status.lock().unwrap() = StatusCode::OK;
+        client.execute(Ping, ()).await.unwrap();
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec![
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Closed
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_trial_opens_circuit_again() {
+        let transport =
+            InMemoryTransport::new().route(Method::GET, "/ping", |_| {
+                HttpResponse::new(StatusCode::BAD_GATEWAY, Vec::new())
+            });
+        let client =
+            Client::with_transport("https://happydog.org", transport.clone())
+                .unwrap()
+                .with_middleware(CircuitBreaker::new(
+                    1,
+                    Duration::from_millis(20),
+                ));
+        assert!(client.execute(Ping, ()).await.is_err());
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let response = client.execute(Ping, ()).await;
+        assert!(matches!(response, Err(ClientError::StatusError(_))));
+        let response = client.execute(Ping, ()).await;
+        assert!(matches!(response, Err(ClientError::CircuitOpen)));
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn huge_cool_down_keeps_circuit_open() {
+        let transport =
+            InMemoryTransport::new().route(Method::GET, "/ping", |_| {
+                HttpResponse::new(StatusCode::BAD_GATEWAY, Vec::new())
+            });
+        let client =
+            Client::with_transport("https://happydog.org", transport.clone())
+                .unwrap()
+                .with_middleware(CircuitBreaker::new(1, Duration::MAX));
+        assert!(client.execute(Ping, ()).await.is_err());
+        let response = client.execute(Ping, ()).await;
+        assert!(matches!(response, Err(ClientError::CircuitOpen)));
+        assert_eq!(transport.requests().len(), 1);
+    }
+}
@@ -0,0 +1,254 @@
+//! Configuration of the `Client` and of the single `Client::execute_with`
+//! call.
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use airactions::{Client, ExecuteOptions};
+//!
+//! let client = Client::builder("https://happydog.org")
+//!     .with_connect_timeout(Duration::from_secs(5))
+//!     .with_read_timeout(Duration::from_secs(10))
+//!     .with_timeout(Duration::from_secs(30))
+//!     .build()
+//!     .unwrap();
+//! // Long running action can be given more time:
+//! let options = ExecuteOptions::new().with_timeout(Duration::from_secs(60));
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::IntoUrl;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+use crate::metrics::Metrics;
+use crate::middleware::Middleware;
+use crate::transport::{ReqwestTransport, Transport};
+use crate::{Client, ClientError, ReqwestClient};
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// ───── ClientBuilder ────────────────────────────────────────────────────── //
+
+/// Builder of the `Client`.
+///
+/// By default connect timeout is 10 seconds, read timeout is 30 seconds
+/// and there is no total timeout.
+pub struct ClientBuilder {
+    address: Result<Url, ClientError>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    metrics: Option<Arc<dyn Metrics>>,
+}
+
+impl ClientBuilder {
+    pub(crate) fn new(url: impl IntoUrl) -> Self {
+        ClientBuilder {
+            address: url.into_url().map_err(ClientError::from),
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            read_timeout: Some(DEFAULT_READ_TIMEOUT),
+            timeout: None,
+            middlewares: Vec::new(),
+            metrics: None,
+        }
+    }
+
+    /// Timeout of establishing connection with the server.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout of waiting for the next chunk of the response.
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout of the whole `Client::execute` call, including all retries
+    /// and middlewares. Can be overridden with `ExecuteOptions`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Disable connect and read timeouts.
+    pub fn without_transport_timeouts(mut self) -> Self {
+        self.connect_timeout = None;
+        self.read_timeout = None;
+        self
+    }
+
+    /// See `Client::with_middleware`.
+    pub fn with_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// See `Client::with_metrics`.
+    pub fn with_metrics(mut self, metrics: impl Metrics) -> Self {
+        self.metrics = Some(Arc::new(metrics));
+        self
+    }
+
+    /// Build client with the `ReqwestTransport`.
+    pub fn build(self) -> Result<Client, ClientError> {
+        let mut builder = ReqwestClient::builder();
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        let transport = ReqwestTransport::new(builder.build()?);
+        self.build_with_transport(transport)
+    }
+
+    /// Build client with the custom transport. Connect and read timeouts
+    /// are the concern of the transport, so they are not applied.
+    pub fn build_with_transport<T: Transport>(
+        self,
+        transport: T,
+    ) -> Result<Client<T>, ClientError> {
+        Ok(Client {
+            transport: Arc::new(transport),
+            address: self.address?,
+            middlewares: self.middlewares,
+            timeout: self.timeout,
+            metrics: self.metrics,
+        })
+    }
+}
+
+impl std::fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("address", &self.address)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("timeout", &self.timeout)
+            .field("middlewares", &self.middlewares.len())
+            .field("metrics", &self.metrics.is_some())
+            .finish()
+    }
+}
+
+// ───── ExecuteOptions ───────────────────────────────────────────────────── //
+
+/// Overrides for the single `Client::execute_with` call.
+#[derive(Debug, Clone, Default)]
+pub struct ExecuteOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) cancellation: Option<CancellationToken>,
+}
+
+impl ExecuteOptions {
+    pub fn new() -> Self {
+        ExecuteOptions::default()
+    }
+
+    /// Total timeout of this call, replaces the one of the `Client`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// When the token is cancelled, the call is aborted at the nearest
+    /// await point with `ClientError::Cancelled`.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio_util::sync::CancellationToken;
+    use url::Url;
+
+    use super::ExecuteOptions;
+    use crate::middleware::BoxFuture;
+    use crate::transport::{HttpRequest, HttpResponse, Transport};
+    use crate::{ApiAction, Client, ClientError, HttpClient, StatusCode};
+
+    /// Transport, which answers after the delay.
+    struct SlowTransport(Duration);
+
+    impl Transport for SlowTransport {
+        fn send(
+            &self,
+            _req: HttpRequest,
+        ) -> BoxFuture<'_, Result<HttpResponse, ClientError>> {
+            Box::pin(async move {
+                tokio::time::sleep(self.0).await;
+                Ok(HttpResponse::new(StatusCode::OK, "Hello!"))
+            })
+        }
+    }
+
+    struct Ping;
+
+    impl ApiAction for Ping {
+        type Request = ();
+        type Response = String;
+        fn url_path(&self) -> &'static str {
+            "/ping"
+        }
+        async fn perform_action(
+            _req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            Ok(client.get(addr).send().await?.text())
+        }
+    }
+
+    fn client(delay: u64, timeout: u64) -> Client<SlowTransport> {
+        Client::builder("https://happydog.org")
+            .with_timeout(Duration::from_millis(timeout))
+            .build_with_transport(SlowTransport(Duration::from_millis(delay)))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn client_timeout_aborts_slow_request() {
+        let response = client(200, 20).execute(Ping, ()).await;
+        assert!(matches!(response, Err(ClientError::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn execute_options_override_client_timeout() {
+        let options =
+            ExecuteOptions::new().with_timeout(Duration::from_secs(5));
+        let response = client(50, 20).execute_with(Ping, (), options).await;
+        assert_eq!(response.unwrap(), "Hello!");
+    }
+
+    #[tokio::test]
+    async fn cancelled_token_aborts_request() {
+        let token = CancellationToken::new();
+        let options = ExecuteOptions::new().with_cancellation(token.clone());
+        let client = client(5_000, 10_000);
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            token.cancel();
+        };
+        let (response, _) =
+            tokio::join!(client.execute_with(Ping, (), options), cancel);
+        assert!(matches!(response, Err(ClientError::Cancelled)));
+    }
+
+    #[test]
+    fn invalid_address_is_reported_on_build() {
+        assert!(Client::builder("not a url").build().is_err());
+    }
+}
@@ -0,0 +1,468 @@
+//! Caching of responses of read-only actions.
+//!
+//! Only actions with `ApiAction::cache_ttl` are cached, the key is
+//! the action with the method, url, headers and body of the request,
+//! so requests with different parameters or credentials (e.g. in the
+//! `Authorization` header) are cached separately. Only successful
+//! (`2xx`) responses are saved.
+//!
+//! Headers, which change with every request (request ids, dates),
+//! make every key unique, use `Cache::with_key_headers` to choose
+//! headers, which identify the response. Headers, which are not in
+//! the key, must not affect the response, or one caller can receive
+//! the response of another.
+//!
+//! `Cache` is a handle: clones share the store, so the one kept
+//! by the application can invalidate entries of the one added
+//! to the `Client`.
+//! ```rust
+//! use airactions::cache::Cache;
+//! use airactions::Client;
+//!
+//! let cache = Cache::new(1_000);
+//! let client = Client::new("https://happydog.org")
+//!     .unwrap()
+//!     .with_middleware(cache.clone());
+//! // After the data was changed:
+//! cache.clear();
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::header::{HeaderName, HeaderValue};
+use url::Url;
+
+use crate::middleware::{ActionContext, BoxFuture, Middleware, Next};
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::{ApiAction, ClientError, Method};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// Type name of the action.
+    pub action: &'static str,
+    pub method: Method,
+    pub url: Url,
+    /// Headers of the request, sorted by name.
+    pub headers: Vec<(HeaderName, HeaderValue)>,
+    pub body: Vec<u8>,
+}
+
+impl CacheKey {
+    /// Key with all headers or only with the `key_headers`.
+    fn new(
+        req: &HttpRequest,
+        ctx: &ActionContext,
+        key_headers: Option<&[HeaderName]>,
+    ) -> Self {
+        let mut headers: Vec<_> = req
+            .headers
+            .iter()
+            .filter(|(name, _)| key_headers.is_none_or(|k| k.contains(name)))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        headers.sort_by(|(a, a_value), (b, b_value)| {
+            a.as_str().cmp(b.as_str()).then(a_value.cmp(b_value))
+        });
+        CacheKey {
+            action: ctx.action,
+            method: req.method.clone(),
+            url: req.url.clone(),
+            headers,
+            body: req.body.clone(),
+        }
+    }
+}
+
+/// Storage of cached responses.
+pub trait CacheStore: Send + Sync + 'static {
+    /// Saved response, if it is not expired.
+    fn get(&self, key: &CacheKey) -> Option<HttpResponse>;
+    fn put(&self, key: CacheKey, response: HttpResponse, ttl: Duration);
+    fn remove_where(&self, predicate: &dyn Fn(&CacheKey) -> bool);
+}
+
+// ───── LruCacheStore ────────────────────────────────────────────────────── //
+
+/// In-memory store, which removes the least recently used response,
+/// when the capacity is exceeded.
+#[derive(Debug)]
+pub struct LruCacheStore {
+    capacity: usize,
+    lru: Mutex<Lru>,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    tick: u64,
+    entries: HashMap<CacheKey, Entry>,
+    /// Keys by the tick of the last use.
+    order: BTreeMap<u64, CacheKey>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    response: HttpResponse,
+    /// `None` if the `ttl` does not fit into `Instant`.
+    expires: Option<Instant>,
+    used: u64,
+}
+
+impl LruCacheStore {
+    pub fn new(capacity: usize) -> Self {
+        LruCacheStore {
+            capacity: capacity.max(1),
+            lru: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Number of stored responses, including expired ones.
+    pub fn len(&self) -> usize {
+        self.lru.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CacheStore for LruCacheStore {
+    fn get(&self, key: &CacheKey) -> Option<HttpResponse> {
+        let mut lru = self.lru.lock().unwrap();
+        lru.tick += 1;
+        let tick = lru.tick;
+        let Lru { entries, order, .. } = &mut *lru;
+        let entry = entries.get_mut(key)?;
+        order.remove(&entry.used);
+        if entry
+            .expires
+            .is_some_and(|expires| Instant::now() >= expires)
+        {
+            entries.remove(key);
+            return None;
+        }
+        entry.used = tick;
+        order.insert(tick, key.clone());
+        Some(entry.response.clone())
+    }
+
+    fn put(&self, key: CacheKey, response: HttpResponse, ttl: Duration) {
+        let mut lru = self.lru.lock().unwrap();
+        lru.tick += 1;
+        let used = lru.tick;
+        let entry = Entry {
+            response,
+            expires: Instant::now().checked_add(ttl),
+            used,
+        };
+        if let Some(old) = lru.entries.insert(key.clone(), entry) {
+            lru.order.remove(&old.used);
+        }
+        lru.order.insert(used, key);
+        while lru.entries.len() > self.capacity {
+            let Some((_, oldest)) = lru.order.pop_first() else {
+                break;
+            };
+            lru.entries.remove(&oldest);
+        }
+    }
+
+    fn remove_where(&self, predicate: &dyn Fn(&CacheKey) -> bool) {
+        let mut lru = self.lru.lock().unwrap();
+        let Lru { entries, order, .. } = &mut *lru;
+        entries.retain(|key, entry| {
+            let remove = predicate(key);
+            if remove {
+                order.remove(&entry.used);
+            }
+            !remove
+        });
+    }
+}
+
+// ───── Cache ────────────────────────────────────────────────────────────── //
+
+#[derive(Clone)]
+pub struct Cache {
+    store: Arc<dyn CacheStore>,
+    key_headers: Option<Arc<[HeaderName]>>,
+}
+
+impl Cache {
+    /// Cache with the `LruCacheStore` of the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Cache::with_store(LruCacheStore::new(capacity))
+    }
+
+    pub fn with_store(store: impl CacheStore) -> Self {
+        Cache {
+            store: Arc::new(store),
+            key_headers: None,
+        }
+    }
+
+    /// Add only given headers to the key instead of all of them.
+    pub fn with_key_headers(mut self, names: &[HeaderName]) -> Self {
+        self.key_headers = Some(names.into());
+        self
+    }
+
+    /// Remove all responses of the action.
+    pub fn invalidate_action<A: ApiAction>(&self) {
+        let action = std::any::type_name::<A>();
+        self.store.remove_where(&|key| key.action == action);
+    }
+
+    /// Remove responses with matching keys.
+    pub fn invalidate_where<F>(&self, predicate: F)
+    where
+        F: Fn(&CacheKey) -> bool,
+    {
+        self.store.remove_where(&predicate);
+    }
+
+    pub fn clear(&self) {
+        self.store.remove_where(&|_| true);
+    }
+}
+
+impl Middleware for Cache {
+    fn handle<'a>(
+        &'a self,
+        req: HttpRequest,
+        ctx: &'a ActionContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            let Some(ttl) = ctx.cache_ttl else {
+                return next.run(req, ctx).await;
+            };
+            let key = CacheKey::new(&req, ctx, self.key_headers.as_deref());
+            if let Some(response) = self.store.get(&key) {
+                tracing::debug!("Response is taken from cache");
+                return Ok(response);
+            }
+            let response = next.run(req, ctx).await?;
+            if response.status.is_success() {
+                self.store.put(key, response.clone(), ttl);
+            }
+            Ok(response)
+        })
+    }
+}
+
+impl std::fmt::Debug for Cache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cache")
+            .field("key_headers", &self.key_headers)
+            .finish_non_exhaustive()
+    }
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+    use url::Url;
+
+    use super::{Cache, CacheKey, CacheStore, LruCacheStore};
+    use crate::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+    use crate::transport::{HttpResponse, InMemoryTransport};
+    use crate::{ApiAction, Client, ClientError, HttpClient};
+    use crate::{Method, StatusCode};
+
+    #[derive(Serialize)]
+    struct Card {
+        token: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Info {
+        version: u16,
+    }
+
+    struct CardInfo(u64);
+
+    impl ApiAction for CardInfo {
+        type Request = Card;
+        type Response = Info;
+        fn url_path(&self) -> &'static str {
+            "/card"
+        }
+        fn cache_ttl(&self) -> Option<Duration> {
+            Some(Duration::from_millis(self.0))
+        }
+        async fn perform_action(
+            req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client
+                .post(addr)
+                .json(&req)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+        }
+    }
+
+    /// Same as `CardInfo`, but with credentials in the header.
+    struct PrivateCardInfo(&'static str);
+
+    impl ApiAction for PrivateCardInfo {
+        type Request = Card;
+        type Response = Info;
+        fn url_path(&self) -> &'static str {
+            "/card"
+        }
+        fn headers(&self) -> HeaderMap {
+            let mut headers = HeaderMap::new();
+            headers.insert(AUTHORIZATION, HeaderValue::from_static(self.0));
+            headers
+        }
+        fn cache_ttl(&self) -> Option<Duration> {
+            Some(Duration::from_secs(60))
+        }
+        async fn perform_action(
+            req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            CardInfo::perform_action(req, addr, client).await
+        }
+    }
+
+    /// Every response has the next version, `broken` card fails.
+    fn client(cache: &Cache) -> Client<InMemoryTransport> {
+        let version = Arc::new(AtomicU16::new(0));
+        let transport =
+            InMemoryTransport::new().route(Method::POST, "/card", move |req| {
+                if req.body.windows(6).any(|w| w == b"broken") {
+                    return HttpResponse::new(StatusCode::BAD_GATEWAY, "");
+                }
+                let version = version.fetch_add(1, Ordering::SeqCst);
+                let body = serde_json::json!({ "version": version });
+                HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+            });
+        Client::with_transport("https://happydog.org", transport)
+            .unwrap()
+            .with_middleware(cache.clone())
+    }
+
+    fn card(token: &str) -> Card {
+        Card {
+            token: token.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn responses_are_cached_per_request() {
+        let cache = Cache::new(10);
+        let client = client(&cache);
+        let first = client.execute(CardInfo(60_000), card("a")).await.unwrap();
+        let cached = client.execute(CardInfo(60_000), card("a")).await.unwrap();
+        let other = client.execute(CardInfo(60_000), card("b")).await.unwrap();
+        assert_eq!(first.version, cached.version);
+        assert_ne!(first.version, other.version);
+        assert_eq!(client.transport().requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn expired_and_invalidated_responses_are_fetched_again() {
+        let cache = Cache::new(10);
+        let client = client(&cache);
+        let first = client.execute(CardInfo(20), card("a")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let second = client.execute(CardInfo(20), card("a")).await.unwrap();
+        assert_ne!(first.version, second.version);
+
+        cache.invalidate_action::<CardInfo>();
+        let third = client.execute(CardInfo(20), card("a")).await.unwrap();
+        assert_ne!(second.version, third.version);
+    }
+
+    #[tokio::test]
+    async fn responses_are_cached_per_credentials() {
+        let per_user = client(&Cache::new(10));
+        let rex = PrivateCardInfo("Bearer rex");
+        let first = per_user.execute(rex, card("a")).await.unwrap();
+        let rex = PrivateCardInfo("Bearer rex");
+        let cached = per_user.execute(rex, card("a")).await.unwrap();
+        let max = PrivateCardInfo("Bearer max");
+        let other = per_user.execute(max, card("a")).await.unwrap();
+        assert_eq!(first.version, cached.version);
+        assert_ne!(first.version, other.version);
+
+        // Authorization is not in the key, so responses are shared.
+        let shared = client(&Cache::new(10).with_key_headers(&[CONTENT_TYPE]));
+        let rex = PrivateCardInfo("Bearer rex");
+        let first = shared.execute(rex, card("a")).await.unwrap();
+        let max = PrivateCardInfo("Bearer max");
+        let cached = shared.execute(max, card("a")).await.unwrap();
+        assert_eq!(first.version, cached.version);
+    }
+
+    #[tokio::test]
+    async fn failed_responses_are_not_cached() {
+        let cache = Cache::new(10);
+        let client = client(&cache);
+        for _ in 0..2 {
+            let response = client.execute(CardInfo(60_000), card("broken"));
+            assert!(response.await.is_err());
+        }
+        assert_eq!(client.transport().requests().len(), 2);
+    }
+
+    #[test]
+    fn least_recently_used_response_is_evicted() {
+        let store = LruCacheStore::new(2);
+        let key = |path: &str| CacheKey {
+            action: "action",
+            method: Method::GET,
+            url: Url::parse("https://happydog.org")
+                .unwrap()
+                .join(path)
+                .unwrap(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        };
+        let response = || HttpResponse::new(StatusCode::OK, "Woof");
+        let ttl = Duration::from_secs(60);
+        store.put(key("/a"), response(), ttl);
+        store.put(key("/b"), response(), ttl);
+        assert!(store.get(&key("/a")).is_some());
+        store.put(key("/c"), response(), ttl);
+        assert!(store.get(&key("/b")).is_none());
+        assert!(store.get(&key("/a")).is_some());
+        assert_eq!(store.len(), 2);
+
+        store.remove_where(&|key| key.url.path() == "/a");
+        assert!(store.get(&key("/a")).is_none());
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn huge_ttl_never_expires() {
+        let store = LruCacheStore::new(10);
+        let key = CacheKey {
+            action: "action",
+            method: Method::GET,
+            url: Url::parse("https://happydog.org").unwrap(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        };
+        store.put(
+            key.clone(),
+            HttpResponse::new(StatusCode::OK, "Woof"),
+            Duration::MAX,
+        );
+        assert!(store.get(&key).is_some());
+    }
+}
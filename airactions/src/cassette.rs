@@ -0,0 +1,394 @@
+//! Record-and-replay transport for offline tests.
+//!
+//! In recording mode `Cassette` sends requests with the wrapped transport
+//! and writes every request/response pair to a json file. In replay mode
+//! it answers requests with recorded responses, without any network access.
+//! Values of secret fields (`Token`, `Password` by default) are scrubbed
+//! before writing, so cassettes are safe to commit.
+//! ```rust,no_run
+//! use airactions::cassette::Cassette;
+//! use airactions::transport::ReqwestTransport;
+//! use airactions::Client;
+//!
+//! // Replays `tests/cassettes/hello.json` if it exists,
+//! // records it with the real transport otherwise.
+//! let cassette =
+//!     Cassette::new("tests/cassettes/hello.json", ReqwestTransport::default())
+//!         .unwrap();
+//! let client =
+//!     Client::with_transport("https://happydog.org", cassette).unwrap();
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+use crate::middleware::BoxFuture;
+use crate::redact::{redact_fields, DEFAULT_SECRET_FIELDS, REDACTED};
+use crate::transport::{HttpRequest, HttpResponse, Transport};
+use crate::{error_chain_fmt, ClientError};
+
+/// Headers, which values are never written to the cassette.
+const SECRET_HEADERS: [&str; 3] = ["authorization", "cookie", "set-cookie"];
+
+#[derive(thiserror::Error)]
+pub enum CassetteError {
+    #[error("Failed to read or write cassette file")]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to parse cassette file")]
+    ParseError(#[from] serde_json::Error),
+    #[error("Recorded interaction is broken: {0}")]
+    BrokenInteraction(String),
+    #[error("No recorded interaction matches request: {0}")]
+    NoMatch(String),
+}
+
+impl std::fmt::Debug for CassetteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// Rules, which are used to find recorded interaction for a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Match {
+    Method,
+    Path,
+    Query,
+    /// Bodies are compared after scrubbing, so json bodies, which differ
+    /// only in secret fields, are considered equal.
+    Body,
+}
+
+// ───── Recorded Types ───────────────────────────────────────────────────── //
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Body {
+    Json(Value),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    url: Url,
+    headers: Vec<(String, String)>,
+    body: Option<Body>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Option<Body>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+// ───── Cassette ─────────────────────────────────────────────────────────── //
+
+pub struct Cassette {
+    path: PathBuf,
+    /// Transport for recording, `None` in replay mode.
+    inner: Option<Box<dyn Transport>>,
+    /// Recorded interactions with the flag, whether it was replayed.
+    interactions: Mutex<Vec<(Interaction, bool)>>,
+    matching: Vec<Match>,
+    secret_fields: Vec<String>,
+}
+
+impl Cassette {
+    /// Replay cassette if the file exists, record it otherwise.
+    pub fn new(
+        path: impl AsRef<Path>,
+        transport: impl Transport,
+    ) -> Result<Self, CassetteError> {
+        if path.as_ref().exists() {
+            Cassette::replay(path)
+        } else {
+            Ok(Cassette::record(path, transport))
+        }
+    }
+
+    /// Send requests with given transport and write them to the file.
+    /// Existing file will be overwritten.
+    pub fn record(path: impl AsRef<Path>, transport: impl Transport) -> Self {
+        Cassette::empty(path.as_ref(), Some(Box::new(transport)))
+    }
+
+    /// Answer requests with interactions, recorded to the file.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        let content = std::fs::read(path.as_ref())?;
+        let interactions: Vec<Interaction> = serde_json::from_slice(&content)?;
+        let cassette = Cassette::empty(path.as_ref(), None);
+        *cassette.interactions.lock().unwrap() =
+            interactions.into_iter().map(|i| (i, false)).collect();
+        Ok(cassette)
+    }
+
+    fn empty(path: &Path, inner: Option<Box<dyn Transport>>) -> Self {
+        Cassette {
+            path: path.to_path_buf(),
+            inner,
+            interactions: Mutex::new(Vec::new()),
+            matching: vec![Match::Method, Match::Path, Match::Body],
+            secret_fields: DEFAULT_SECRET_FIELDS
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+        }
+    }
+
+    /// Replace default matching rules (method, path and body).
+    pub fn with_matching(mut self, rules: &[Match]) -> Self {
+        self.matching = rules.to_vec();
+        self
+    }
+
+    /// Scrub one more json field (case-insensitive).
+    pub fn with_secret_field(mut self, field: &str) -> Self {
+        self.secret_fields.push(field.to_string());
+        self
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    fn scrub_body(&self, body: &[u8]) -> Option<Body> {
+        if body.is_empty() {
+            return None;
+        }
+        match serde_json::from_slice::<Value>(body) {
+            Ok(mut json) => {
+                redact_fields(&mut json, &self.secret_fields);
+                Some(Body::Json(json))
+            }
+            Err(_) => Some(Body::Text(String::from_utf8_lossy(body).into())),
+        }
+    }
+
+    fn matches(&self, recorded: &RecordedRequest, req: &HttpRequest) -> bool {
+        self.matching.iter().all(|rule| match rule {
+            Match::Method => recorded.method.eq(req.method.as_str()),
+            Match::Path => recorded.url.path().eq(req.url.path()),
+            Match::Query => recorded.url.query().eq(&req.url.query()),
+            Match::Body => recorded.body.eq(&self.scrub_body(&req.body)),
+        })
+    }
+
+    fn record_interaction(
+        &self,
+        req: &HttpRequest,
+        response: &HttpResponse,
+    ) -> Result<(), CassetteError> {
+        let interaction = Interaction {
+            request: RecordedRequest {
+                method: req.method.to_string(),
+                url: req.url.clone(),
+                headers: scrub_headers(&req.headers),
+                body: self.scrub_body(&req.body),
+            },
+            response: RecordedResponse {
+                status: response.status.as_u16(),
+                headers: scrub_headers(&response.headers),
+                body: self.scrub_body(&response.body),
+            },
+        };
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.push((interaction, false));
+        let recorded: Vec<_> = interactions.iter().map(|(i, _)| i).collect();
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&recorded)?)?;
+        Ok(())
+    }
+
+    fn replay_interaction(
+        &self,
+        req: &HttpRequest,
+    ) -> Result<HttpResponse, CassetteError> {
+        let mut interactions = self.interactions.lock().unwrap();
+        let (interaction, replayed) = interactions
+            .iter_mut()
+            .find(|(i, replayed)| !replayed && self.matches(&i.request, req))
+            .ok_or_else(|| {
+                CassetteError::NoMatch(format!("{} {}", req.method, req.url))
+            })?;
+        *replayed = true;
+        let recorded = &interaction.response;
+        let status = StatusCode::from_u16(recorded.status)
+            .map_err(|e| CassetteError::BrokenInteraction(e.to_string()))?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in recorded.headers.iter() {
+            headers.append(
+                HeaderName::try_from(name.as_str()).map_err(|e| {
+                    CassetteError::BrokenInteraction(e.to_string())
+                })?,
+                HeaderValue::try_from(value.as_str()).map_err(|e| {
+                    CassetteError::BrokenInteraction(e.to_string())
+                })?,
+            );
+        }
+        let body = match recorded.body {
+            Some(Body::Json(ref json)) => serde_json::to_vec(json)?,
+            Some(Body::Text(ref text)) => text.clone().into_bytes(),
+            None => Vec::new(),
+        };
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+impl Transport for Cassette {
+    fn send(
+        &self,
+        req: HttpRequest,
+    ) -> BoxFuture<'_, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            match self.inner {
+                Some(ref inner) => {
+                    let response = inner.send(req.clone()).await?;
+                    self.record_interaction(&req, &response)?;
+                    Ok(response)
+                }
+                None => Ok(self.replay_interaction(&req)?),
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for Cassette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cassette")
+            .field("path", &self.path)
+            .field("recording", &self.is_recording())
+            .field("matching", &self.matching)
+            .finish()
+    }
+}
+
+fn scrub_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SECRET_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use url::Url;
+
+    use super::{Cassette, CassetteError};
+    use crate::transport::{HttpResponse, InMemoryTransport};
+    use crate::{ApiAction, Client, ClientError, HttpClient};
+    use crate::{Method, StatusCode};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Login {
+        user: String,
+        password: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Session {
+        id: u32,
+        token: String,
+    }
+
+    struct DoLogin;
+
+    impl ApiAction for DoLogin {
+        type Request = Login;
+        type Response = Session;
+        fn url_path(&self) -> &'static str {
+            "/login"
+        }
+        async fn perform_action(
+            req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client.post(addr).json(&req).send().await?.json()
+        }
+    }
+
+    fn login(password: &str) -> Login {
+        Login {
+            user: "dog".to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    fn cassette_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir()
+            .join(format!("airactions-{}-{name}.json", std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn recorded_interaction_is_scrubbed_and_replayed() {
+        let path = cassette_path("login");
+        let transport =
+            InMemoryTransport::new().route(Method::POST, "/login", |_| {
+                let session = Session {
+                    id: 7,
+                    token: "very-secret".to_string(),
+                };
+                HttpResponse::from_json(StatusCode::OK, &session).unwrap()
+            });
+        let client = Client::with_transport(
+            "https://happydog.org",
+            Cassette::record(&path, transport),
+        )
+        .unwrap();
+        client.execute(DoLogin, login("qwerty")).await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("qwerty"));
+        assert!(!content.contains("very-secret"));
+
+        // Password differs, but it is a secret field, so it is not compared
+        let client = Client::with_transport(
+            "https://happydog.org",
+            Cassette::replay(&path).unwrap(),
+        )
+        .unwrap();
+        let session = client.execute(DoLogin, login("12345")).await.unwrap();
+        assert_eq!(session.id, 7);
+        assert_eq!(session.token, "[REDACTED]");
+
+        // Every interaction is replayed only once
+        let result = client.execute(DoLogin, login("12345")).await;
+        assert!(matches!(
+            result,
+            Err(ClientError::CassetteError(CassetteError::NoMatch(_)))
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+}
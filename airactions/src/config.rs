@@ -0,0 +1,289 @@
+//! Environment profiles: base URL, credentials and timeouts of the API,
+//! loaded from a file or environment variables.
+//!
+//! Base URL is taken from `base_url`, or from the backend `Preset`
+//! named by `environment`. Profile file is a json object with profiles
+//! by name:
+//! ```json
+//! {
+//!   "sandbox": {
+//!     "environment": "test",
+//!     "key": "TinkoffBankTest",
+//!     "password": "secret",
+//!     "timeout_secs": 30
+//!   },
+//!   "production": {
+//!     "base_url": "https://securepay.tinkoff.ru/v2",
+//!     "key": "Merchant",
+//!     "password": "secret"
+//!   }
+//! }
+//! ```
+//! Environment variables have the same names in upper case with a prefix,
+//! e.g. `TINKOFF_ENVIRONMENT`, `TINKOFF_KEY`, `TINKOFF_PASSWORD`.
+//! ```rust
+//! use airactions::config::{Preset, Profile};
+//!
+//! const PRESETS: &[Preset] = &[Preset::new("local", "http://localhost:15100")];
+//!
+//! let vars = |name: &str| match name {
+//!     "BANKSIM_ENVIRONMENT" => Some("local".to_string()),
+//!     "BANKSIM_PASSWORD" => Some("secret".to_string()),
+//!     _ => None,
+//! };
+//! let profile = Profile::from_vars("BANKSIM", vars).unwrap();
+//! let client = profile.client(PRESETS).unwrap();
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use secrecy::Secret;
+use serde::Deserialize;
+use url::Url;
+
+use crate::{error_chain_fmt, Client, ClientBuilder, ClientError};
+
+#[derive(thiserror::Error)]
+pub enum ConfigError {
+    #[error("Failed to read profile file")]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to parse profile file")]
+    ParseError(#[from] serde_json::Error),
+    #[error("No profile named {0}")]
+    UnknownProfile(String),
+    #[error("Environment variable {0} is not set")]
+    MissingVar(String),
+    #[error("Environment variable {0} is invalid")]
+    InvalidVar(String),
+    #[error("Unknown environment: {0}")]
+    UnknownEnvironment(String),
+    #[error("Credentials key is not set")]
+    MissingKey,
+    #[error("Neither base url, nor environment is set")]
+    NoBaseUrl,
+    #[error("Failed to parse base url")]
+    UrlError(#[from] url::ParseError),
+    #[error("Failed to build client")]
+    ClientError(#[from] ClientError),
+}
+
+impl std::fmt::Debug for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// Known environment of the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preset {
+    pub name: &'static str,
+    pub base_url: &'static str,
+}
+
+impl Preset {
+    pub const fn new(name: &'static str, base_url: &'static str) -> Self {
+        Preset { name, base_url }
+    }
+}
+
+// ───── Profile ──────────────────────────────────────────────────────────── //
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Profile {
+    /// Name of the `Preset`, used when `base_url` is not set.
+    #[serde(default)]
+    pub environment: Option<String>,
+    #[serde(default)]
+    pub base_url: Option<Url>,
+    /// Public part of credentials: terminal key, login, etc.
+    #[serde(default)]
+    pub key: Option<String>,
+    pub password: Secret<String>,
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub read_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl Profile {
+    /// Load the profile by name from the json file.
+    pub fn from_file(
+        path: impl AsRef<Path>,
+        name: &str,
+    ) -> Result<Self, ConfigError> {
+        let file = std::fs::read(path)?;
+        let mut profiles: HashMap<String, Profile> =
+            serde_json::from_slice(&file)?;
+        profiles
+            .remove(name)
+            .ok_or_else(|| ConfigError::UnknownProfile(name.to_string()))
+    }
+
+    /// Load the profile from `{prefix}_*` environment variables.
+    pub fn from_env(prefix: &str) -> Result<Self, ConfigError> {
+        Profile::from_vars(prefix, |name| std::env::var(name).ok())
+    }
+
+    /// Load the profile from `{prefix}_*` variables of the given source.
+    pub fn from_vars<F>(prefix: &str, vars: F) -> Result<Self, ConfigError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let var = |name: &str| vars(&format!("{prefix}_{name}"));
+        let secs = |name: &str| {
+            var(name)
+                .map(|value| {
+                    value.parse().map_err(|_| {
+                        ConfigError::InvalidVar(format!("{prefix}_{name}"))
+                    })
+                })
+                .transpose()
+        };
+        Ok(Profile {
+            environment: var("ENVIRONMENT"),
+            base_url: var("BASE_URL").map(|url| url.parse()).transpose()?,
+            key: var("KEY"),
+            password: var("PASSWORD").map(Secret::new).ok_or_else(|| {
+                ConfigError::MissingVar(format!("{prefix}_PASSWORD"))
+            })?,
+            connect_timeout_secs: secs("CONNECT_TIMEOUT_SECS")?,
+            read_timeout_secs: secs("READ_TIMEOUT_SECS")?,
+            timeout_secs: secs("TIMEOUT_SECS")?,
+        })
+    }
+
+    /// `base_url` of the profile, or the url of the `environment` preset.
+    pub fn base_url(&self, presets: &[Preset]) -> Result<Url, ConfigError> {
+        if let Some(ref url) = self.base_url {
+            return Ok(url.clone());
+        }
+        let environment =
+            self.environment.as_deref().ok_or(ConfigError::NoBaseUrl)?;
+        let preset = presets
+            .iter()
+            .find(|preset| preset.name == environment)
+            .ok_or_else(|| {
+            ConfigError::UnknownEnvironment(environment.to_string())
+        })?;
+        Ok(preset.base_url.parse()?)
+    }
+
+    /// Builder with the base url and timeouts of the profile, middlewares
+    /// and metrics can be added before build.
+    pub fn client_builder(
+        &self,
+        presets: &[Preset],
+    ) -> Result<ClientBuilder, ConfigError> {
+        let mut builder = Client::builder(self.base_url(presets)?);
+        if let Some(secs) = self.connect_timeout_secs {
+            builder = builder.with_connect_timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.read_timeout_secs {
+            builder = builder.with_read_timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.timeout_secs {
+            builder = builder.with_timeout(Duration::from_secs(secs));
+        }
+        Ok(builder)
+    }
+
+    pub fn client(&self, presets: &[Preset]) -> Result<Client, ConfigError> {
+        Ok(self.client_builder(presets)?.build()?)
+    }
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use secrecy::ExposeSecret;
+
+    use super::{ConfigError, Preset, Profile};
+
+    const PRESETS: &[Preset] = &[
+        Preset::new("production", "https://securepay.tinkoff.ru/v2"),
+        Preset::new("test", "https://rest-api-test.tinkoff.ru/v2"),
+    ];
+
+    fn vars(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone())
+    }
+
+    #[test]
+    fn profile_is_loaded_from_file_by_name() {
+        let path = std::env::temp_dir()
+            .join(format!("airactions_profiles_{}.json", std::process::id()));
+        let file = serde_json::json!({
+            "sandbox": {
+                "environment": "test",
+                "key": "TinkoffBankTest",
+                "password": "secret",
+                "timeout_secs": 30
+            }
+        });
+        std::fs::write(&path, file.to_string()).unwrap();
+
+        let profile = Profile::from_file(&path, "sandbox").unwrap();
+        assert_eq!(profile.key.as_deref(), Some("TinkoffBankTest"));
+        assert_eq!(profile.password.expose_secret(), "secret");
+        assert_eq!(
+            profile.base_url(PRESETS).unwrap().as_str(),
+            "https://rest-api-test.tinkoff.ru/v2"
+        );
+        assert!(matches!(
+            Profile::from_file(&path, "production"),
+            Err(ConfigError::UnknownProfile(_))
+        ));
+    }
+
+    #[test]
+    fn base_url_overrides_environment() {
+        let profile = Profile::from_vars(
+            "TINKOFF",
+            vars(&[
+                ("TINKOFF_ENVIRONMENT", "production"),
+                ("TINKOFF_BASE_URL", "http://localhost:8080/v2"),
+                ("TINKOFF_PASSWORD", "secret"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            profile.base_url(PRESETS).unwrap().as_str(),
+            "http://localhost:8080/v2"
+        );
+    }
+
+    #[test]
+    fn invalid_variables_are_reported() {
+        let profile = Profile::from_vars("TINKOFF", vars(&[]));
+        assert!(matches!(profile, Err(ConfigError::MissingVar(_))));
+        let profile = Profile::from_vars(
+            "TINKOFF",
+            vars(&[
+                ("TINKOFF_PASSWORD", "secret"),
+                ("TINKOFF_TIMEOUT_SECS", "soon"),
+            ]),
+        );
+        assert!(matches!(profile, Err(ConfigError::InvalidVar(_))));
+        let profile = Profile::from_vars(
+            "TINKOFF",
+            vars(&[
+                ("TINKOFF_PASSWORD", "secret"),
+                ("TINKOFF_ENVIRONMENT", "staging"),
+            ]),
+        )
+        .unwrap();
+        assert!(matches!(
+            profile.base_url(PRESETS),
+            Err(ConfigError::UnknownEnvironment(_))
+        ));
+    }
+}
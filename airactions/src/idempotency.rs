@@ -0,0 +1,453 @@
+//! Deduplication of repeated operations by idempotency key.
+//!
+//! Actions declare the key with `ApiAction::idempotency_key`. The first
+//! final response for the key (see `ApiAction::is_final_response`,
+//! `2xx` by default) is saved in the `IdempotencyStore`, repeated
+//! requests with the same key within the window are answered from the
+//! store without touching the network. The key must not be reused with
+//! a different request: the fingerprint of the method, url and body is
+//! saved with the response, and a mismatch fails with
+//! `IdempotencyError::KeyReused`. Concurrent requests with the same key
+//! wait for the first one. Keys are scoped by action, requests without
+//! key pass through.
+//!
+//! Add the middleware before `retry::RetryPolicy`, so the response
+//! is saved once for all attempts.
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use airactions::idempotency::Idempotency;
+//! use airactions::retry::RetryPolicy;
+//! use airactions::Client;
+//!
+//! let client = Client::new("https://happydog.org")
+//!     .unwrap()
+//!     .with_middleware(Idempotency::new(Duration::from_secs(86_400)))
+//!     .with_middleware(RetryPolicy::new(3));
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::header::{HeaderName, HeaderValue};
+use sha2::{Digest, Sha256};
+
+use crate::middleware::{ActionContext, BoxFuture, Middleware, Next};
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::{error_chain_fmt, ClientError};
+
+/// Lock of the key with the number of requests, which hold or wait for it.
+type KeyLocks = Mutex<HashMap<String, (Arc<tokio::sync::Mutex<()>>, usize)>>;
+
+#[derive(thiserror::Error)]
+pub enum IdempotencyError {
+    #[error("Idempotency key {0} is reused with a different request")]
+    KeyReused(String),
+}
+
+impl std::fmt::Debug for IdempotencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// Saved response with the fingerprint of the request.
+#[derive(Debug, Clone)]
+pub struct IdempotentResponse {
+    /// SHA-256 of the method, url and body of the request.
+    pub fingerprint: String,
+    pub response: HttpResponse,
+}
+
+/// Storage of responses by idempotency key, can be backed by a shared
+/// cache to deduplicate requests of several service instances.
+pub trait IdempotencyStore: Send + Sync + 'static {
+    /// Saved response, if it is not expired.
+    fn get<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<IdempotentResponse>, ClientError>>;
+    /// Save the response for the `ttl` period.
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        response: IdempotentResponse,
+        ttl: Duration,
+    ) -> BoxFuture<'a, Result<(), ClientError>>;
+}
+
+// ───── InMemoryIdempotencyStore ─────────────────────────────────────────── //
+
+/// Store, local to the process. Expired responses are removed on `put`.
+#[derive(Debug, Default)]
+pub struct InMemoryIdempotencyStore {
+    /// Responses by key with the expiration time, `None` if the `ttl`
+    /// does not fit into `Instant` and the response never expires.
+    responses: Mutex<HashMap<String, (Option<Instant>, IdempotentResponse)>>,
+}
+
+impl InMemoryIdempotencyStore {
+    pub fn new() -> Self {
+        InMemoryIdempotencyStore::default()
+    }
+}
+
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    fn get<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<IdempotentResponse>, ClientError>> {
+        let responses = self.responses.lock().unwrap();
+        let response = responses
+            .get(key)
+            .filter(|(expires, _)| is_alive(*expires, Instant::now()))
+            .map(|(_, response)| response.clone());
+        Box::pin(async move { Ok(response) })
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        response: IdempotentResponse,
+        ttl: Duration,
+    ) -> BoxFuture<'a, Result<(), ClientError>> {
+        let now = Instant::now();
+        let mut responses = self.responses.lock().unwrap();
+        responses.retain(|_, (expires, _)| is_alive(*expires, now));
+        responses.insert(key.to_string(), (now.checked_add(ttl), response));
+        Box::pin(async { Ok(()) })
+    }
+}
+
+fn is_alive(expires: Option<Instant>, now: Instant) -> bool {
+    expires.is_none_or(|expires| now < expires)
+}
+
+// ───── Idempotency ──────────────────────────────────────────────────────── //
+
+pub struct Idempotency {
+    window: Duration,
+    store: Arc<dyn IdempotencyStore>,
+    header: Option<HeaderName>,
+    locks: KeyLocks,
+}
+
+impl Idempotency {
+    /// Deduplicate requests within the `window` with the in-memory store.
+    pub fn new(window: Duration) -> Self {
+        Idempotency {
+            window,
+            store: Arc::new(InMemoryIdempotencyStore::new()),
+            header: None,
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Replace the default `InMemoryIdempotencyStore`.
+    pub fn with_store(mut self, store: impl IdempotencyStore) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+    /// Also send the key to the server in the given header,
+    /// e.g. `Idempotency-Key`.
+    pub fn with_header(mut self, name: HeaderName) -> Self {
+        self.header = Some(name);
+        self
+    }
+
+    fn key_lock(&self, key: String) -> KeyLock<'_> {
+        let mut locks = self.locks.lock().unwrap();
+        let (lock, waiters) = locks.entry(key.clone()).or_default();
+        *waiters += 1;
+        let lock = lock.clone();
+        KeyLock {
+            locks: &self.locks,
+            key,
+            lock,
+        }
+    }
+}
+
+impl Middleware for Idempotency {
+    fn handle<'a>(
+        &'a self,
+        mut req: HttpRequest,
+        ctx: &'a ActionContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            let Some(ref key) = ctx.idempotency_key else {
+                return next.run(req, ctx).await;
+            };
+            if let Some(ref name) = self.header {
+                let value = HeaderValue::try_from(key.as_str())
+                    .map_err(|e| ClientError::MiddlewareError(Box::new(e)))?;
+                req.headers.insert(name.clone(), value);
+            }
+            let fingerprint = fingerprint(&req);
+            let key_lock = self.key_lock(format!("{}:{}", ctx.action, key));
+            let _guard = key_lock.lock.clone().lock_owned().await;
+            if let Some(saved) = self.store.get(&key_lock.key).await? {
+                if saved.fingerprint != fingerprint {
+                    let e = IdempotencyError::KeyReused(key.clone());
+                    return Err(ClientError::MiddlewareError(Box::new(e)));
+                }
+                tracing::debug!(
+                    key,
+                    "Response is taken from idempotency store"
+                );
+                return Ok(saved.response);
+            }
+            let response = next.run(req, ctx).await?;
+            if (ctx.is_final_response)(&response) {
+                let saved = IdempotentResponse {
+                    fingerprint,
+                    response: response.clone(),
+                };
+                self.store.put(&key_lock.key, saved, self.window).await?;
+            }
+            Ok(response)
+        })
+    }
+}
+
+impl std::fmt::Debug for Idempotency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Idempotency")
+            .field("window", &self.window)
+            .field("header", &self.header)
+            .finish()
+    }
+}
+
+/// Removes the lock of the key, when nobody else waits for it.
+struct KeyLock<'a> {
+    locks: &'a KeyLocks,
+    key: String,
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl Drop for KeyLock<'_> {
+    fn drop(&mut self) {
+        let mut locks = self.locks.lock().unwrap();
+        if let Some((_, waiters)) = locks.get_mut(&self.key) {
+            *waiters -= 1;
+            if *waiters == 0 {
+                locks.remove(&self.key);
+            }
+        }
+    }
+}
+
+fn fingerprint(req: &HttpRequest) -> String {
+    let digest = Sha256::new()
+        .chain_update(req.method.as_str())
+        .chain_update([0])
+        .chain_update(req.url.as_str())
+        .chain_update([0])
+        .chain_update(&req.body)
+        .finalize();
+    format!("{:x}", digest)
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+    use url::Url;
+
+    use super::{Idempotency, IdempotencyError};
+    use crate::header::HeaderName;
+    use crate::transport::{HttpResponse, InMemoryTransport};
+    use crate::{ApiAction, Client, ClientError, HttpClient};
+    use crate::{Method, StatusCode};
+
+    #[derive(Serialize)]
+    struct Order {
+        order_id: u32,
+        amount: u32,
+    }
+
+    fn order(order_id: u32) -> Order {
+        Order {
+            order_id,
+            amount: 100,
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Created {
+        number: u16,
+    }
+
+    struct CreateOrder;
+
+    impl ApiAction for CreateOrder {
+        type Request = Order;
+        type Response = Created;
+        fn url_path(&self) -> &'static str {
+            "/orders"
+        }
+        fn idempotency_key(&self, req: &Self::Request) -> Option<String> {
+            Some(req.order_id.to_string())
+        }
+        async fn perform_action(
+            req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client
+                .post(addr)
+                .json(&req)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+        }
+    }
+
+    /// Every order gets the next number, the first one fails.
+    fn transport() -> InMemoryTransport {
+        let counter = Arc::new(AtomicU16::new(0));
+        InMemoryTransport::new().route(Method::POST, "/orders", move |_| {
+            let number = counter.fetch_add(1, Ordering::SeqCst);
+            if number == 0 {
+                return HttpResponse::new(StatusCode::BAD_GATEWAY, Vec::new());
+            }
+            let body = serde_json::json!({ "number": number });
+            HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+        })
+    }
+
+    fn client(window: Duration) -> Client<InMemoryTransport> {
+        Client::with_transport("https://happydog.org", transport())
+            .unwrap()
+            .with_middleware(
+                Idempotency::new(window)
+                    .with_header(HeaderName::from_static("idempotency-key")),
+            )
+    }
+
+    #[tokio::test]
+    async fn repeated_key_is_answered_from_store() {
+        let client = client(Duration::from_secs(60));
+        let failed = client.execute(CreateOrder, order(7)).await;
+        assert!(matches!(failed, Err(ClientError::StatusError(_))));
+        let first = client.execute(CreateOrder, order(7)).await.unwrap();
+        let repeated = client.execute(CreateOrder, order(7)).await.unwrap();
+        assert_eq!(first.number, repeated.number);
+        let other = client.execute(CreateOrder, order(8)).await.unwrap();
+        assert_ne!(first.number, other.number);
+
+        let requests = client.transport().requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].headers["idempotency-key"], "7");
+    }
+
+    #[tokio::test]
+    async fn concurrent_duplicates_are_sent_once() {
+        let client = client(Duration::from_secs(60));
+        let _ = client.execute(CreateOrder, order(0)).await;
+        let (a, b) = tokio::join!(
+            client.execute(CreateOrder, order(1)),
+            client.execute(CreateOrder, order(1)),
+        );
+        assert_eq!(a.unwrap().number, b.unwrap().number);
+        assert_eq!(client.transport().requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reused_key_with_different_request_fails() {
+        let client = client(Duration::from_secs(60));
+        let _ = client.execute(CreateOrder, order(1)).await;
+        client.execute(CreateOrder, order(1)).await.unwrap();
+        let changed = Order {
+            order_id: 1,
+            amount: 200,
+        };
+        let Err(ClientError::MiddlewareError(e)) =
+            client.execute(CreateOrder, changed).await
+        else {
+            panic!("Request with reused key is not rejected");
+        };
+        assert!(matches!(
+            e.downcast_ref::<IdempotencyError>(),
+            Some(IdempotencyError::KeyReused(_))
+        ));
+        assert_eq!(client.transport().requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn not_final_responses_are_not_saved() {
+        struct CheckedOrder;
+
+        impl ApiAction for CheckedOrder {
+            type Request = Order;
+            type Response = Created;
+            fn url_path(&self) -> &'static str {
+                "/orders"
+            }
+            fn idempotency_key(&self, req: &Self::Request) -> Option<String> {
+                Some(req.order_id.to_string())
+            }
+            /// Only even numbers are final.
+            fn is_final_response(response: &HttpResponse) -> bool {
+                let body: serde_json::Value =
+                    serde_json::from_slice(&response.body).unwrap_or_default();
+                body["number"].as_u64().is_some_and(|n| n % 2 == 0)
+            }
+            async fn perform_action(
+                req: Self::Request,
+                addr: Url,
+                client: &HttpClient<'_>,
+            ) -> Result<Self::Response, ClientError> {
+                CreateOrder::perform_action(req, addr, client).await
+            }
+        }
+
+        let client = client(Duration::from_secs(60));
+        let _ = client.execute(CheckedOrder, order(1)).await;
+        let odd = client.execute(CheckedOrder, order(1)).await.unwrap();
+        let even = client.execute(CheckedOrder, order(1)).await.unwrap();
+        let saved = client.execute(CheckedOrder, order(1)).await.unwrap();
+        assert_eq!((odd.number, even.number), (1, 2));
+        assert_eq!(saved.number, 2);
+    }
+
+    #[test]
+    fn key_locks_are_removed_after_requests() {
+        let idempotency = Idempotency::new(Duration::from_secs(60));
+        let first = idempotency.key_lock("a".to_string());
+        let second = idempotency.key_lock("a".to_string());
+        let extra = first.lock.clone();
+        drop(first);
+        assert_eq!(idempotency.locks.lock().unwrap()["a"].1, 1);
+        drop(second);
+        assert!(idempotency.locks.lock().unwrap().is_empty());
+        drop(extra);
+    }
+
+    #[tokio::test]
+    async fn key_expires_after_window() {
+        let client = client(Duration::from_millis(20));
+        let _ = client.execute(CreateOrder, order(1)).await;
+        let first = client.execute(CreateOrder, order(1)).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let second = client.execute(CreateOrder, order(1)).await;
+        assert_ne!(first.unwrap().number, second.unwrap().number);
+    }
+
+    #[tokio::test]
+    async fn huge_window_never_expires() {
+        let client = client(Duration::MAX);
+        let _ = client.execute(CreateOrder, order(1)).await;
+        let first = client.execute(CreateOrder, order(1)).await.unwrap();
+        let second = client.execute(CreateOrder, order(1)).await.unwrap();
+        assert_eq!(first.number, second.number);
+    }
+}
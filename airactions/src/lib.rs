@@ -1,10 +1,49 @@
 use std::future::Future;
+use std::sync::atomic::AtomicU32;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use reqwest::header::HeaderMap;
 use reqwest::IntoUrl;
-use url::Url;
+use tracing::Instrument;
 
+use metrics::{Metrics, Outcome};
+use middleware::{ActionContext, Middleware, Next};
+use transport::{HttpResponse, ReqwestTransport, Transport};
+
+#[cfg(feature = "derive")]
+pub use airactions_derive::ApiAction;
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingClient;
+pub use builder::{ClientBuilder, ExecuteOptions};
+pub use request::{HttpClient, RequestBuilder};
+pub use reqwest::header;
 pub use reqwest::Client as ReqwestClient;
-pub use reqwest::StatusCode;
+pub use reqwest::{Method, StatusCode};
+pub use tokio_util::sync::CancellationToken;
+pub use url::Url;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod breaker;
+mod builder;
+pub mod cache;
+pub mod cassette;
+pub mod config;
+pub mod idempotency;
+pub mod limit;
+pub mod metrics;
+pub mod middleware;
+pub mod paginate;
+pub mod path;
+mod redact;
+mod request;
+pub mod retry;
+pub mod signing;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod trace;
+pub mod transport;
 
 #[derive(thiserror::Error)]
 pub enum ClientError {
@@ -12,6 +51,33 @@ pub enum ClientError {
     ReqwestError(#[from] reqwest::Error),
     #[error("Failed to parse url")]
     UrlError(#[from] url::ParseError),
+    #[error("Middleware error")]
+    MiddlewareError(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Failed to serialize json")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Unexpected response status: {}", .0.status)]
+    StatusError(Box<transport::HttpResponse>),
+    #[error("Failed to decode response body")]
+    DecodeError {
+        #[source]
+        source: serde_json::Error,
+        /// Raw body, which failed to decode.
+        body: String,
+    },
+    #[error("Request timed out")]
+    Timeout,
+    #[error("Request was cancelled")]
+    Cancelled,
+    #[error("Circuit breaker is open")]
+    CircuitOpen,
+    #[error("Failed to start async runtime")]
+    RuntimeError(#[source] std::io::Error),
+    #[error("Failed to serialize query")]
+    QueryError(#[from] serde_urlencoded::ser::Error),
+    #[error("Cassette error")]
+    CassetteError(#[from] cassette::CassetteError),
+    #[error("No value for path parameter: {0}")]
+    MissingPathParam(String),
 }
 
 pub(crate) fn error_chain_fmt(
@@ -35,9 +101,10 @@ pub(crate) fn error_chain_fmt(
 /// ```rust
 /// use serde::Deserialize;
 /// use url::Url;
-/// use acquiconnect::AcquiClient;
-/// use acquiconnect::ClientError;
-/// use acquiconnect::ApiAction;
+/// use airactions::Client;
+/// use airactions::ClientError;
+/// use airactions::ApiAction;
+/// use airactions::HttpClient;
 ///
 /// // Define action struct
 /// pub struct SayHello;
@@ -56,7 +123,7 @@ pub(crate) fn error_chain_fmt(
 ///     async fn perform_action(
 ///         req: Self::Request,
 ///         _addr: Url,
-///         _client: &reqwest::Client,
+///         _client: &HttpClient<'_>,
 ///     ) -> Result<Self::Response, ClientError> {
 ///         let name = req.0;
 ///         Ok(SimpleResponse(format!("Hello, {name}!")))
@@ -65,7 +132,7 @@ pub(crate) fn error_chain_fmt(
 ///
 /// // Now we can use that action:
 /// async fn run() {
-/// let client = AcquiClient::new("https://happydog.org").unwrap();
+/// let client = Client::new("https://happydog.org").unwrap();
 /// let response = client
 ///     .execute(SayHello, SimpleRequest("Dog".to_string()))
 ///     .await
@@ -75,11 +142,53 @@ pub(crate) fn error_chain_fmt(
 pub trait ApiAction {
     type Request;
     type Response;
+    /// Whether it is safe to send the same request more than once.
+    /// Non-idempotent actions are never repeated by `retry::RetryPolicy`
+    /// after the request could reach the server.
+    const IDEMPOTENT: bool = false;
+    /// Path of the action, it is appended to the path of the `Client`
+    /// address, see `path::join`. It can contain `{name}` placeholders,
+    /// which are filled with `path_params`.
     fn url_path(&self) -> &'static str;
+    /// Http method of the action, `POST` by default.
+    fn method(&self) -> Method {
+        Method::POST
+    }
+    /// Headers, added to every request of the action.
+    fn headers(&self) -> HeaderMap {
+        HeaderMap::new()
+    }
+    /// Values for `{name}` placeholders in the `url_path`, they are
+    /// percent-encoded before substitution.
+    fn path_params(&self, _req: &Self::Request) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+    /// Query parameters, appended to the action url.
+    fn query(&self, _req: &Self::Request) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+    /// Key, which identifies the operation, e.g. order id. Repeated
+    /// requests with the same key are answered from the store by
+    /// `idempotency::Idempotency`.
+    fn idempotency_key(&self, _req: &Self::Request) -> Option<String> {
+        None
+    }
+    /// Whether the response is the final outcome of the operation, which
+    /// `idempotency::Idempotency` can save for repeated requests. `2xx`
+    /// by default, APIs, which report failures inside of a `200` body,
+    /// should check the body too.
+    fn is_final_response(response: &HttpResponse) -> bool {
+        response.status.is_success()
+    }
+    /// How long successful responses can be reused by `cache::Cache`,
+    /// `None` (default) disables caching of the action.
+    fn cache_ttl(&self) -> Option<Duration> {
+        None
+    }
     fn perform_action(
         req: Self::Request,
         addr: Url,
-        client: &ReqwestClient,
+        client: &HttpClient<'_>,
     ) -> impl Future<Output = Result<Self::Response, ClientError>> + Send;
 }
 
@@ -89,30 +198,178 @@ impl std::fmt::Debug for ClientError {
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct Client {
-    client: ReqwestClient,
+pub struct Client<T = ReqwestTransport> {
+    transport: Arc<T>,
     address: Url,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    timeout: Option<Duration>,
+    metrics: Option<Arc<dyn Metrics>>,
 }
 
 impl Client {
+    /// Client with default timeouts, see `ClientBuilder`.
     pub fn new(url: impl IntoUrl) -> Result<Self, ClientError> {
-        Ok(Client {
-            client: reqwest::Client::new(),
-            address: url.into_url()?,
-        })
+        Client::builder(url).build()
+    }
+    pub fn builder(url: impl IntoUrl) -> ClientBuilder {
+        ClientBuilder::new(url)
+    }
+}
+
+impl<T: Transport> Client<T> {
+    pub fn with_transport(
+        url: impl IntoUrl,
+        transport: T,
+    ) -> Result<Self, ClientError> {
+        ClientBuilder::new(url).build_with_transport(transport)
+    }
+    pub fn transport(&self) -> &T {
+        &self.transport
     }
-    pub async fn execute<T: ApiAction>(
+    /// Push middleware on top of the stack, every executed action
+    /// will pass through it.
+    pub fn with_middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+    /// Report count, outcome and duration of every executed action.
+    pub fn with_metrics(mut self, metrics: impl Metrics) -> Self {
+        self.metrics = Some(Arc::new(metrics));
+        self
+    }
+    /// Execute action through the middleware stack and the transport.
+    ///
+    /// Every call is wrapped in the `airactions.execute` tracing span with
+    /// action name, url path, method, number of attempts, last status
+    /// and latency. Request and response bodies are logged with `TRACE`
+    /// level of the `airactions::body` target, with `Token`, `Password`
+    /// fields redacted and card numbers masked.
+    pub async fn execute<A: ApiAction>(
         &self,
-        action: T,
-        data: T::Request,
-    ) -> Result<T::Response, ClientError> {
-        T::perform_action(
-            data,
-            self.address.join(action.url_path())?,
-            &self.client,
-        )
-        .await
+        action: A,
+        data: A::Request,
+    ) -> Result<A::Response, ClientError> {
+        self.execute_with(action, data, ExecuteOptions::default())
+            .await
+    }
+    /// Execute action with overridden timeout and/or cancellation token.
+    pub async fn execute_with<A: ApiAction>(
+        &self,
+        action: A,
+        data: A::Request,
+        options: ExecuteOptions,
+    ) -> Result<A::Response, ClientError> {
+        let action_name = std::any::type_name::<A>();
+        let method = action.method();
+        let ctx = ActionContext {
+            action: action_name,
+            url_path: action.url_path(),
+            span: trace::action_span(action_name, action.url_path(), &method),
+            method,
+            idempotent: A::IDEMPOTENT,
+            idempotency_key: action.idempotency_key(&data),
+            is_final_response: A::is_final_response,
+            cache_ttl: action.cache_ttl(),
+            attempts: AtomicU32::new(0),
+        };
+        let started = Instant::now();
+        let call = async {
+            match options.timeout.or(self.timeout) {
+                Some(timeout) => tokio::time::timeout(
+                    timeout,
+                    self.perform(&ctx, action, data),
+                )
+                .await
+                .map_err(|_| ClientError::Timeout)?,
+                None => self.perform(&ctx, action, data).await,
+            }
+        };
+        let call = call.instrument(ctx.span.clone());
+        let result = match options.cancellation {
+            Some(ref token) => token
+                .run_until_cancelled(call)
+                .await
+                .unwrap_or(Err(ClientError::Cancelled)),
+            None => call.await,
+        };
+        trace::record_result(&ctx.span, started, &result);
+        if let Some(ref metrics) = self.metrics {
+            metrics::record(
+                metrics.as_ref(),
+                ctx.action,
+                Outcome::of(&result),
+                started.elapsed(),
+            );
+        }
+        result
+    }
+    async fn perform<A: ApiAction>(
+        &self,
+        ctx: &ActionContext,
+        action: A,
+        data: A::Request,
+    ) -> Result<A::Response, ClientError> {
+        let path = path::fill(ctx.url_path, &action.path_params(&data))?;
+        let mut url = path::join(&self.address, &path)?;
+        let query = action.query(&data);
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        let client = HttpClient::new(
+            Next::new(self.transport.as_ref(), &self.middlewares),
+            ctx,
+            action.headers(),
+        );
+        A::perform_action(data, url, &client).await
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use serde::Serialize;
+    use serde_json::Value;
+
+    /// Used by `#[derive(ApiAction)]`: take path parameters from the
+    /// fields of the serialized request.
+    pub fn path_params<T: Serialize + ?Sized>(
+        req: &T,
+        names: &[&'static str],
+    ) -> Vec<(&'static str, String)> {
+        let Ok(Value::Object(fields)) = serde_json::to_value(req) else {
+            return Vec::new();
+        };
+        names
+            .iter()
+            .filter_map(|name| match fields.get(*name)? {
+                Value::Null => None,
+                Value::String(value) => Some((*name, value.clone())),
+                value => Some((*name, value.to_string())),
+            })
+            .collect()
+    }
+}
+
+impl<T> Clone for Client<T> {
+    fn clone(&self) -> Self {
+        Client {
+            transport: self.transport.clone(),
+            address: self.address.clone(),
+            middlewares: self.middlewares.clone(),
+            timeout: self.timeout,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Client<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("transport", &self.transport)
+            .field("address", &self.address)
+            .field("middlewares", &self.middlewares.len())
+            .field("timeout", &self.timeout)
+            .field("metrics", &self.metrics.is_some())
+            .finish()
     }
 }
 
@@ -146,7 +403,10 @@ mod tests {
     use serde::Deserialize;
     use url::Url;
 
-    use super::{ApiAction, Client, ClientError};
+    use super::header::{HeaderMap, HeaderValue};
+    use super::transport::{HttpResponse, InMemoryTransport};
+    use super::{ApiAction, Client, ClientError, HttpClient};
+    use super::{Method, StatusCode};
 
     pub struct SayHello;
     pub struct SimpleRequest(pub String);
@@ -162,7 +422,7 @@ mod tests {
         async fn perform_action(
             req: Self::Request,
             _addr: Url,
-            _client: &reqwest::Client,
+            _client: &HttpClient<'_>,
         ) -> Result<Self::Response, ClientError> {
             let name = req.0;
             Ok(SimpleResponse(format!("Hello, {name}!")))
@@ -178,4 +438,62 @@ mod tests {
             .unwrap();
         assert_eq!(response.0, "Hello, Dog!".to_string())
     }
+
+    pub struct DeleteSession;
+
+    impl ApiAction for DeleteSession {
+        type Request = String;
+        type Response = ();
+        fn url_path(&self) -> &'static str {
+            "session/{id}"
+        }
+        fn method(&self) -> Method {
+            Method::DELETE
+        }
+        fn headers(&self) -> HeaderMap {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-api-version", HeaderValue::from_static("2"));
+            headers
+        }
+        fn path_params(&self, req: &String) -> Vec<(&'static str, String)> {
+            vec![("id", req.clone())]
+        }
+        fn query(&self, _req: &String) -> Vec<(&'static str, String)> {
+            vec![("force", "true".to_string())]
+        }
+        async fn perform_action(
+            _req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client
+                .request(client.method().clone(), addr)
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn action_declares_method_path_params_query_and_headers() {
+        let transport = InMemoryTransport::new().route(
+            Method::DELETE,
+            "/api/session/a%2Fb%20c",
+            |_| HttpResponse::new(StatusCode::NO_CONTENT, Vec::new()),
+        );
+        let client = Client::with_transport(
+            "https://happydog.org/api/",
+            transport.clone(),
+        )
+        .unwrap();
+        client
+            .execute(DeleteSession, "a/b c".to_string())
+            .await
+            .unwrap();
+
+        let received = transport.requests();
+        assert_eq!(received[0].url.query(), Some("force=true"));
+        assert_eq!(received[0].headers["x-api-version"], "2");
+    }
 }
@@ -0,0 +1,347 @@
+//! Client-side rate limiting and concurrency caps.
+//!
+//! Both limiters delay requests instead of failing them. By default one
+//! limit is shared by all actions of the `Client`, with `per_path` every
+//! `ApiAction::url_path` gets its own limit.
+//!
+//! Middlewares, added after the `RetryPolicy`, limit every attempt,
+//! added before it - every executed action.
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use airactions::limit::{ConcurrencyLimit, RateLimit};
+//! use airactions::retry::RetryPolicy;
+//! use airactions::Client;
+//!
+//! let client = Client::new("https://happydog.org")
+//!     .unwrap()
+//!     .with_middleware(RetryPolicy::new(3))
+//!     .with_middleware(ConcurrencyLimit::new(4))
+//!     .with_middleware(
+//!         RateLimit::new(10, Duration::from_secs(1)).with_burst(20),
+//!     );
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Semaphore;
+
+use crate::middleware::{ActionContext, BoxFuture, Middleware, Next};
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::ClientError;
+
+/// Key of the limit, shared by all actions.
+const SHARED: &str = "";
+
+// ───── RateLimit ────────────────────────────────────────────────────────── //
+
+/// Token bucket: `requests` tokens are added every `per` period, up to
+/// the `burst` capacity, every request takes one token.
+#[derive(Debug)]
+pub struct RateLimit {
+    rate: f64,
+    burst: f64,
+    per_path: bool,
+    buckets: Mutex<HashMap<&'static str, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimit {
+    /// Allow `requests` requests every `per` period, burst capacity
+    /// equals to `requests`.
+    pub fn new(requests: u32, per: Duration) -> Self {
+        let requests = requests.max(1) as f64;
+        RateLimit {
+            rate: requests / per.as_secs_f64().max(f64::EPSILON),
+            burst: requests,
+            per_path: false,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Maximum number of requests, which can be sent without delay
+    /// after a period of inactivity.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1) as f64;
+        self
+    }
+    /// Keep separate bucket for every `ApiAction::url_path`.
+    pub fn per_path(mut self) -> Self {
+        self.per_path = true;
+        self
+    }
+
+    /// Take a token and return the reservation with the delay, after
+    /// which the request is allowed. Tokens are reserved in advance,
+    /// so waiting requests are released in order.
+    fn reserve(&self, path: &'static str) -> Reservation<'_> {
+        let key = if self.per_path { path } else { SHARED };
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.updated = now;
+        bucket.tokens -= 1.0;
+        let delay = if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            // Huge `per` periods don't fit into `Duration`.
+            Duration::try_from_secs_f64(-bucket.tokens / self.rate)
+                .unwrap_or(Duration::MAX)
+        };
+        Reservation {
+            limit: self,
+            key,
+            delay,
+            used: false,
+        }
+    }
+}
+
+/// Token, taken from the bucket. If the request is cancelled while
+/// waiting, the token is returned on drop.
+struct Reservation<'a> {
+    limit: &'a RateLimit,
+    key: &'static str,
+    delay: Duration,
+    used: bool,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if self.used {
+            return;
+        }
+        let mut buckets = self.limit.buckets.lock().unwrap();
+        if let Some(bucket) = buckets.get_mut(self.key) {
+            bucket.tokens = (bucket.tokens + 1.0).min(self.limit.burst);
+        }
+    }
+}
+
+impl Middleware for RateLimit {
+    fn handle<'a>(
+        &'a self,
+        req: HttpRequest,
+        ctx: &'a ActionContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            let mut reservation = self.reserve(ctx.url_path);
+            if !reservation.delay.is_zero() {
+                tracing::debug!(
+                    delay = ?reservation.delay,
+                    "Request is delayed by rate limit"
+                );
+                tokio::time::sleep(reservation.delay).await;
+            }
+            reservation.used = true;
+            next.run(req, ctx).await
+        })
+    }
+}
+
+// ───── ConcurrencyLimit ─────────────────────────────────────────────────── //
+
+/// Maximum number of requests in flight, the rest wait for a free slot.
+#[derive(Debug)]
+pub struct ConcurrencyLimit {
+    max_in_flight: usize,
+    per_path: bool,
+    semaphores: Mutex<HashMap<&'static str, Arc<Semaphore>>>,
+}
+
+impl ConcurrencyLimit {
+    pub fn new(max_in_flight: usize) -> Self {
+        ConcurrencyLimit {
+            max_in_flight: max_in_flight.max(1),
+            per_path: false,
+            semaphores: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Keep separate limit for every `ApiAction::url_path`.
+    pub fn per_path(mut self) -> Self {
+        self.per_path = true;
+        self
+    }
+
+    fn semaphore(&self, path: &'static str) -> Arc<Semaphore> {
+        let key = if self.per_path { path } else { SHARED };
+        self.semaphores
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_in_flight)))
+            .clone()
+    }
+}
+
+impl Middleware for ConcurrencyLimit {
+    fn handle<'a>(
+        &'a self,
+        req: HttpRequest,
+        ctx: &'a ActionContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            let _permit = self
+                .semaphore(ctx.url_path)
+                .acquire_owned()
+                .await
+                .expect("Semaphore is never closed");
+            next.run(req, ctx).await
+        })
+    }
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use url::Url;
+
+    use super::{ConcurrencyLimit, RateLimit, SHARED};
+    use crate::middleware::{ActionContext, BoxFuture, Middleware, Next};
+    use crate::transport::{HttpRequest, HttpResponse, Transport};
+    use crate::{ApiAction, Client, ClientError, HttpClient, StatusCode};
+
+    /// Transport, which tracks the maximum number of requests in flight.
+    #[derive(Default)]
+    struct TrackingTransport {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl Transport for TrackingTransport {
+        fn send(
+            &self,
+            _req: HttpRequest,
+        ) -> BoxFuture<'_, Result<HttpResponse, ClientError>> {
+            Box::pin(async move {
+                let current = 1 + self.in_flight.fetch_add(1, Ordering::SeqCst);
+                self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(HttpResponse::new(StatusCode::OK, Vec::new()))
+            })
+        }
+    }
+
+    struct Ping;
+
+    impl ApiAction for Ping {
+        type Request = ();
+        type Response = ();
+        fn url_path(&self) -> &'static str {
+            "/ping"
+        }
+        async fn perform_action(
+            _req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client.get(addr).send().await?.error_for_status()?;
+            Ok(())
+        }
+    }
+
+    async fn run_concurrently<T: Transport>(client: Client<T>, count: usize) {
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..count {
+            let client = client.clone();
+            tasks.spawn(async move { client.execute(Ping, ()).await });
+        }
+        while let Some(result) = tasks.join_next().await {
+            result.unwrap().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limit_delays_requests_over_burst() {
+        let client = Client::with_transport(
+            "https://happydog.org",
+            TrackingTransport::default(),
+        )
+        .unwrap()
+        .with_middleware(RateLimit::new(1, Duration::from_millis(50)));
+        let started = Instant::now();
+        run_concurrently(client, 3).await;
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn per_path_rate_limit_keeps_separate_buckets() {
+        let limit = RateLimit::new(1, Duration::from_secs(60)).per_path();
+        limit.reserve("/dogs").used = true;
+        assert!(limit.reserve("/cats").delay.is_zero());
+        assert!(!limit.reserve("/dogs").delay.is_zero());
+    }
+
+    /// Shares the limit with the test.
+    struct Shared(Arc<RateLimit>);
+
+    impl Middleware for Shared {
+        fn handle<'a>(
+            &'a self,
+            req: HttpRequest,
+            ctx: &'a ActionContext,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+            self.0.handle(req, ctx, next)
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_request_returns_token() {
+        let limit = Arc::new(RateLimit::new(1, Duration::from_secs(60)));
+        let client = Client::with_transport(
+            "https://happydog.org",
+            TrackingTransport::default(),
+        )
+        .unwrap()
+        .with_middleware(Shared(limit.clone()));
+        client.execute(Ping, ()).await.unwrap();
+        // Waits for a minute, so it is cancelled by timeout.
+        let waiting = client.execute(Ping, ());
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(10), waiting).await;
+        assert!(cancelled.is_err());
+        // Without refund the bucket would be one token in debt.
+        let tokens = limit.buckets.lock().unwrap()[SHARED].tokens;
+        assert!(tokens > -0.5);
+    }
+
+    #[test]
+    fn huge_period_does_not_overflow() {
+        let limit = RateLimit::new(1, Duration::MAX);
+        limit.reserve(SHARED).used = true;
+        assert_eq!(limit.reserve(SHARED).delay, Duration::MAX);
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit_caps_requests_in_flight() {
+        let client = Client::with_transport(
+            "https://happydog.org",
+            TrackingTransport::default(),
+        )
+        .unwrap()
+        .with_middleware(ConcurrencyLimit::new(2));
+        run_concurrently(client.clone(), 6).await;
+        let max_in_flight = &client.transport().max_in_flight;
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+}
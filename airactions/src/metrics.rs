@@ -0,0 +1,236 @@
+//! Metrics of executed actions.
+//!
+//! After every `Client::execute` call the `Metrics` implementation
+//! receives:
+//! - `airactions_requests_total` counter increment,
+//! - `airactions_request_duration_seconds` histogram value,
+//!
+//! both labeled with `action` (type name of the action) and `outcome`
+//! (see `Outcome::as_str`).
+//! ```rust
+//! use airactions::metrics::InMemoryMetrics;
+//! use airactions::Client;
+//!
+//! let metrics = InMemoryMetrics::new();
+//! let client = Client::new("https://happydog.org")
+//!     .unwrap()
+//!     .with_metrics(metrics.clone());
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::ClientError;
+
+pub const REQUESTS_TOTAL: &str = "airactions_requests_total";
+pub const REQUEST_DURATION_SECONDS: &str =
+    "airactions_request_duration_seconds";
+
+/// Sink for metrics, can be backed by any metrics library.
+pub trait Metrics: Send + Sync + 'static {
+    fn increment_counter(&self, name: &'static str, labels: &[(&str, &str)]);
+    fn record_histogram(
+        &self,
+        name: &'static str,
+        value: f64,
+        labels: &[(&str, &str)],
+    );
+}
+
+/// Class of the `Client::execute` result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    /// Server responded with non-2xx status.
+    HttpError,
+    /// Response body can't be decoded.
+    DecodeError,
+    Timeout,
+    /// Request could not be delivered.
+    TransportError,
+    /// Request was not sent: cancelled, rejected by middleware, etc.
+    Other,
+}
+
+impl Outcome {
+    pub fn of<T>(result: &Result<T, ClientError>) -> Self {
+        match result {
+            Ok(_) => Outcome::Success,
+            Err(ClientError::StatusError(_)) => Outcome::HttpError,
+            Err(ClientError::DecodeError { .. }) => Outcome::DecodeError,
+            Err(ClientError::Timeout) => Outcome::Timeout,
+            Err(ClientError::ReqwestError(e)) if e.is_timeout() => {
+                Outcome::Timeout
+            }
+            Err(ClientError::ReqwestError(_)) => Outcome::TransportError,
+            Err(_) => Outcome::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::HttpError => "http_error",
+            Outcome::DecodeError => "decode_error",
+            Outcome::Timeout => "timeout",
+            Outcome::TransportError => "transport_error",
+            Outcome::Other => "other",
+        }
+    }
+}
+
+pub(crate) fn record(
+    metrics: &dyn Metrics,
+    action: &'static str,
+    outcome: Outcome,
+    duration: Duration,
+) {
+    let labels = [("action", action), ("outcome", outcome.as_str())];
+    metrics.increment_counter(REQUESTS_TOTAL, &labels);
+    metrics.record_histogram(
+        REQUEST_DURATION_SECONDS,
+        duration.as_secs_f64(),
+        &labels,
+    );
+}
+
+// ───── InMemoryMetrics ──────────────────────────────────────────────────── //
+
+type Key = (&'static str, Vec<(String, String)>);
+
+/// Metrics, stored in memory, useful in tests.
+///
+/// Cloned instances share the stored values.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryMetrics {
+    counters: Arc<Mutex<HashMap<Key, u64>>>,
+    histograms: Arc<Mutex<HashMap<Key, Vec<f64>>>>,
+}
+
+impl InMemoryMetrics {
+    pub fn new() -> Self {
+        InMemoryMetrics::default()
+    }
+
+    /// Value of the counter with exactly given labels (in any order).
+    pub fn counter(&self, name: &'static str, labels: &[(&str, &str)]) -> u64 {
+        let counters = self.counters.lock().unwrap();
+        counters.get(&key(name, labels)).copied().unwrap_or(0)
+    }
+
+    /// Recorded values of the histogram with exactly given labels
+    /// (in any order).
+    pub fn histogram(
+        &self,
+        name: &'static str,
+        labels: &[(&str, &str)],
+    ) -> Vec<f64> {
+        let histograms = self.histograms.lock().unwrap();
+        histograms
+            .get(&key(name, labels))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl Metrics for InMemoryMetrics {
+    fn increment_counter(&self, name: &'static str, labels: &[(&str, &str)]) {
+        let mut counters = self.counters.lock().unwrap();
+        *counters.entry(key(name, labels)).or_default() += 1;
+    }
+
+    fn record_histogram(
+        &self,
+        name: &'static str,
+        value: f64,
+        labels: &[(&str, &str)],
+    ) {
+        let mut histograms = self.histograms.lock().unwrap();
+        histograms.entry(key(name, labels)).or_default().push(value);
+    }
+}
+
+fn key(name: &'static str, labels: &[(&str, &str)]) -> Key {
+    let mut labels: Vec<(String, String)> = labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    labels.sort();
+    (name, labels)
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use url::Url;
+
+    use super::{InMemoryMetrics, REQUESTS_TOTAL, REQUEST_DURATION_SECONDS};
+    use crate::transport::{HttpResponse, InMemoryTransport};
+    use crate::{ApiAction, Client, ClientError, HttpClient};
+    use crate::{Method, StatusCode};
+
+    #[derive(Serialize, Deserialize)]
+    struct Greeting {
+        name: String,
+    }
+
+    struct Greet;
+
+    impl ApiAction for Greet {
+        type Request = ();
+        type Response = Greeting;
+        fn url_path(&self) -> &'static str {
+            "/greet"
+        }
+        async fn perform_action(
+            _req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client.get(addr).send().await?.error_for_status()?.json()
+        }
+    }
+
+    #[tokio::test]
+    async fn outcomes_are_counted_per_action() {
+        let transport =
+            InMemoryTransport::new().route(Method::GET, "/greet", |_| {
+                HttpResponse::new(StatusCode::OK, "<html>Oops</html>")
+            });
+        let metrics = InMemoryMetrics::new();
+        let client = Client::with_transport("https://happydog.org", transport)
+            .unwrap()
+            .with_metrics(metrics.clone());
+        let _ = client.execute(Greet, ()).await;
+        let _ = client.execute(Greet, ()).await;
+
+        let action = std::any::type_name::<Greet>();
+        let labels = [("outcome", "decode_error"), ("action", action)];
+        assert_eq!(metrics.counter(REQUESTS_TOTAL, &labels), 2);
+        assert_eq!(
+            metrics.histogram(REQUEST_DURATION_SECONDS, &labels).len(),
+            2
+        );
+        let labels = [("action", action), ("outcome", "success")];
+        assert_eq!(metrics.counter(REQUESTS_TOTAL, &labels), 0);
+    }
+
+    #[tokio::test]
+    async fn http_errors_are_classified() {
+        let metrics = InMemoryMetrics::new();
+        let client = Client::with_transport(
+            "https://happydog.org",
+            InMemoryTransport::new(),
+        )
+        .unwrap()
+        .with_metrics(metrics.clone());
+        let _ = client.execute(Greet, ()).await;
+
+        let action = std::any::type_name::<Greet>();
+        let labels = [("action", action), ("outcome", "http_error")];
+        assert_eq!(metrics.counter(REQUESTS_TOTAL, &labels), 1);
+    }
+}
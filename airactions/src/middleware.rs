@@ -0,0 +1,263 @@
+//! Middleware stack, which every `ApiAction` passes through.
+//!
+//! Middlewares are executed in the order they were added to the `Client`:
+//! the first one sees the request first and the response last.
+//! ```rust
+//! use airactions::middleware::{on_request, on_response};
+//! use airactions::Client;
+//!
+//! let client = Client::new("https://happydog.org")
+//!     .unwrap()
+//!     .with_middleware(on_request(|req, _ctx| {
+//!         req.headers.insert("X-Api-Key", "secret".parse().unwrap());
+//!         Ok(())
+//!     }))
+//!     .with_middleware(on_response(|resp, ctx| {
+//!         println!("{} responded with {}", ctx.action, resp.status);
+//!         Ok(())
+//!     }));
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::AtomicU32;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::transport::{HttpRequest, HttpResponse, Transport};
+use crate::{ClientError, Method};
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Information about the action being executed.
+#[derive(Debug)]
+pub struct ActionContext {
+    /// Type name of the action.
+    pub action: &'static str,
+    /// Path, declared by the action.
+    pub url_path: &'static str,
+    /// See `ApiAction::method`.
+    pub method: Method,
+    /// See `ApiAction::IDEMPOTENT`.
+    pub idempotent: bool,
+    /// See `ApiAction::idempotency_key`.
+    pub idempotency_key: Option<String>,
+    /// See `ApiAction::is_final_response`.
+    pub is_final_response: fn(&HttpResponse) -> bool,
+    /// See `ApiAction::cache_ttl`.
+    pub cache_ttl: Option<Duration>,
+    pub(crate) span: tracing::Span,
+    pub(crate) attempts: AtomicU32,
+}
+
+impl ActionContext {
+    /// Span of the executed action, see `Client::execute`.
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
+    }
+}
+
+/// Cross-cutting behaviour, shared by all actions executed with a `Client`.
+///
+/// Implementation can inspect and modify the request, pass it further
+/// with `next.run(req, ctx)`, and inspect or replace the response.
+/// It is also allowed to short-circuit and not to call `next` at all.
+pub trait Middleware: Send + Sync + 'static {
+    fn handle<'a>(
+        &'a self,
+        req: HttpRequest,
+        ctx: &'a ActionContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>>;
+}
+
+/// The rest of the middleware stack, ending with the transport call.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    transport: &'a dyn Transport,
+    middlewares: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        transport: &'a dyn Transport,
+        middlewares: &'a [Arc<dyn Middleware>],
+    ) -> Self {
+        Next {
+            transport,
+            middlewares,
+        }
+    }
+
+    pub fn run(
+        self,
+        req: HttpRequest,
+        ctx: &'a ActionContext,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+        match self.middlewares.split_first() {
+            Some((current, rest)) => current.handle(
+                req,
+                ctx,
+                Next {
+                    transport: self.transport,
+                    middlewares: rest,
+                },
+            ),
+            None => crate::trace::send(self.transport, req, ctx),
+        }
+    }
+}
+
+// ───── Interceptors ─────────────────────────────────────────────────────── //
+
+/// Middleware, which calls the closure for every outgoing request.
+pub struct RequestInterceptor<F>(F);
+
+/// Middleware, which calls the closure for every received response.
+pub struct ResponseInterceptor<F>(F);
+
+pub fn on_request<F>(f: F) -> RequestInterceptor<F>
+where
+    F: Fn(&mut HttpRequest, &ActionContext) -> Result<(), ClientError>
+        + Send
+        + Sync
+        + 'static,
+{
+    RequestInterceptor(f)
+}
+
+pub fn on_response<F>(f: F) -> ResponseInterceptor<F>
+where
+    F: Fn(&mut HttpResponse, &ActionContext) -> Result<(), ClientError>
+        + Send
+        + Sync
+        + 'static,
+{
+    ResponseInterceptor(f)
+}
+
+impl<F> Middleware for RequestInterceptor<F>
+where
+    F: Fn(&mut HttpRequest, &ActionContext) -> Result<(), ClientError>
+        + Send
+        + Sync
+        + 'static,
+{
+    fn handle<'a>(
+        &'a self,
+        mut req: HttpRequest,
+        ctx: &'a ActionContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            (self.0)(&mut req, ctx)?;
+            next.run(req, ctx).await
+        })
+    }
+}
+
+impl<F> Middleware for ResponseInterceptor<F>
+where
+    F: Fn(&mut HttpResponse, &ActionContext) -> Result<(), ClientError>
+        + Send
+        + Sync
+        + 'static,
+{
+    fn handle<'a>(
+        &'a self,
+        req: HttpRequest,
+        ctx: &'a ActionContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            let mut response = next.run(req, ctx).await?;
+            (self.0)(&mut response, ctx)?;
+            Ok(response)
+        })
+    }
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::{on_request, ActionContext, BoxFuture, Middleware, Next};
+    use crate::transport::{HttpRequest, HttpResponse};
+    use crate::{ApiAction, Client, ClientError, HttpClient, StatusCode};
+
+    /// Answers with the value of `X-Trace` header, never calls `next`.
+    struct Echo;
+
+    impl Middleware for Echo {
+        fn handle<'a>(
+            &'a self,
+            req: HttpRequest,
+            _ctx: &'a ActionContext,
+            _next: Next<'a>,
+        ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+            let trace = req
+                .headers
+                .get("X-Trace")
+                .map(|v| v.to_str().unwrap().to_string())
+                .unwrap_or_default();
+            Box::pin(
+                async move { Ok(HttpResponse::new(StatusCode::OK, trace)) },
+            )
+        }
+    }
+
+    struct Ping;
+
+    impl ApiAction for Ping {
+        type Request = ();
+        type Response = String;
+        fn url_path(&self) -> &'static str {
+            "ping"
+        }
+        async fn perform_action(
+            _req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            Ok(client.post(addr).send().await?.text())
+        }
+    }
+
+    fn append(tag: &'static str) -> impl Middleware {
+        on_request(move |req, _| {
+            let current = req
+                .headers
+                .get("X-Trace")
+                .map(|v| v.to_str().unwrap().to_string())
+                .unwrap_or_default();
+            req.headers
+                .insert("X-Trace", format!("{current}{tag}").parse().unwrap());
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn middlewares_are_called_in_order() {
+        let client = Client::new("https://happydog.org")
+            .unwrap()
+            .with_middleware(append("a"))
+            .with_middleware(append("b"))
+            .with_middleware(Echo);
+        let response = client.execute(Ping, ()).await.unwrap();
+        assert_eq!(response, "ab");
+    }
+
+    #[tokio::test]
+    async fn interceptor_error_aborts_request() {
+        let client = Client::new("https://happydog.org")
+            .unwrap()
+            .with_middleware(on_request(|_, ctx| {
+                Err(ClientError::MiddlewareError(ctx.url_path.into()))
+            }))
+            .with_middleware(Echo);
+        let response = client.execute(Ping, ()).await;
+        assert!(matches!(response, Err(ClientError::MiddlewareError(_))));
+    }
+}
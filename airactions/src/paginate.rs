@@ -0,0 +1,243 @@
+//! Listing endpoints, which return collections page by page.
+//!
+//! `PaginatedAction` splits a page into items and builds the request
+//! of the next page, `Client::paginate` turns it into a `Stream` of items.
+//! Pages are fetched lazily, one `Client::execute` call (with all
+//! middlewares, metrics and tracing) per page, when the items of the
+//! previous one are consumed.
+//! ```rust
+//! use airactions::paginate::PaginatedAction;
+//! use airactions::{ApiAction, Client, ClientError, HttpClient, Url};
+//! use futures_util::TryStreamExt;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize)]
+//! struct ListCards {
+//!     offset: u32,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct CardsPage {
+//!     cards: Vec<String>,
+//!     total: u32,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct Cards;
+//!
+//! impl ApiAction for Cards {
+//!     type Request = ListCards;
+//!     type Response = CardsPage;
+//!     fn url_path(&self) -> &'static str {
+//!         "/cards"
+//!     }
+//!     async fn perform_action(
+//!         req: Self::Request,
+//!         addr: Url,
+//!         client: &HttpClient<'_>,
+//!     ) -> Result<Self::Response, ClientError> {
+//!         client.post(addr).json(&req).send().await?.json()
+//!     }
+//! }
+//!
+//! impl PaginatedAction for Cards {
+//!     type Item = String;
+//!     fn next_page(
+//!         req: &ListCards,
+//!         page: CardsPage,
+//!     ) -> (Vec<String>, Option<ListCards>) {
+//!         let offset = req.offset + page.cards.len() as u32;
+//!         let next = (offset < page.total && !page.cards.is_empty())
+//!             .then_some(ListCards { offset });
+//!         (page.cards, next)
+//!     }
+//! }
+//!
+//! async fn all_cards(client: &Client) -> Result<Vec<String>, ClientError> {
+//!     client.paginate(Cards, ListCards { offset: 0 }).try_collect().await
+//! }
+//! ```
+
+use std::collections::VecDeque;
+
+use futures_util::stream::{self, Stream};
+
+use crate::transport::Transport;
+use crate::{ApiAction, Client, ClientError};
+
+/// Action, which returns one page of a collection.
+pub trait PaginatedAction: ApiAction + Clone
+where
+    Self::Request: Clone,
+{
+    type Item;
+    /// Split the page into items and the request of the next page,
+    /// `None` if the page is the last one.
+    fn next_page(
+        req: &Self::Request,
+        page: Self::Response,
+    ) -> (Vec<Self::Item>, Option<Self::Request>);
+}
+
+struct State<A: PaginatedAction>
+where
+    A::Request: Clone,
+{
+    next: Option<A::Request>,
+    items: VecDeque<A::Item>,
+}
+
+impl<T: Transport> Client<T> {
+    /// Stream of items of all pages, starting from the `first` request.
+    ///
+    /// Stream ends after the last page or after the first error.
+    pub fn paginate<A>(
+        &self,
+        action: A,
+        first: A::Request,
+    ) -> impl Stream<Item = Result<A::Item, ClientError>> + '_
+    where
+        A: PaginatedAction + 'static,
+        A::Request: Clone,
+    {
+        let state = State::<A> {
+            next: Some(first),
+            items: VecDeque::new(),
+        };
+        stream::unfold(Some(state), move |state| {
+            let action = action.clone();
+            async move {
+                let mut state = state?;
+                loop {
+                    if let Some(item) = state.items.pop_front() {
+                        return Some((Ok(item), Some(state)));
+                    }
+                    let req = state.next.take()?;
+                    match self.execute(action.clone(), req.clone()).await {
+                        Ok(page) => {
+                            let (items, next) = A::next_page(&req, page);
+                            state.items.extend(items);
+                            state.next = next;
+                        }
+                        Err(e) => return Some((Err(e), None)),
+                    }
+                }
+            }
+        })
+    }
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{StreamExt, TryStreamExt};
+    use serde::{Deserialize, Serialize};
+    use url::Url;
+
+    use super::PaginatedAction;
+    use crate::transport::{HttpResponse, InMemoryTransport};
+    use crate::{ApiAction, Client, ClientError, HttpClient};
+    use crate::{Method, StatusCode};
+
+    #[derive(Clone, Serialize)]
+    struct Page {
+        page: u32,
+    }
+
+    #[derive(Deserialize)]
+    struct Dogs {
+        dogs: Vec<String>,
+        has_more: bool,
+    }
+
+    #[derive(Clone)]
+    struct ListDogs;
+
+    impl ApiAction for ListDogs {
+        type Request = Page;
+        type Response = Dogs;
+        fn url_path(&self) -> &'static str {
+            "/dogs"
+        }
+        async fn perform_action(
+            req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client
+                .post(addr)
+                .json(&req)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+        }
+    }
+
+    impl PaginatedAction for ListDogs {
+        type Item = String;
+        fn next_page(req: &Page, page: Dogs) -> (Vec<String>, Option<Page>) {
+            let next = page.has_more.then_some(Page { page: req.page + 1 });
+            (page.dogs, next)
+        }
+    }
+
+    fn transport(pages: u32) -> InMemoryTransport {
+        InMemoryTransport::new().route(Method::POST, "/dogs", move |req| {
+            let page: serde_json::Value =
+                serde_json::from_slice(&req.body).unwrap();
+            let page = page["page"].as_u64().unwrap() as u32;
+            if page >= pages {
+                return HttpResponse::new(StatusCode::NOT_FOUND, Vec::new());
+            }
+            let body = serde_json::json!({
+                "dogs": [format!("rex-{page}"), format!("max-{page}")],
+                "has_more": page + 1 < pages,
+            });
+            HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+        })
+    }
+
+    #[tokio::test]
+    async fn items_of_all_pages_are_streamed() {
+        let client =
+            Client::with_transport("https://happydog.org", transport(3))
+                .unwrap();
+        let dogs: Vec<String> = client
+            .paginate(ListDogs, Page { page: 0 })
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            dogs,
+            ["rex-0", "max-0", "rex-1", "max-1", "rex-2", "max-2"]
+        );
+        assert_eq!(client.transport().requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn pages_are_fetched_lazily() {
+        let client =
+            Client::with_transport("https://happydog.org", transport(10))
+                .unwrap();
+        let dogs: Vec<_> = client
+            .paginate(ListDogs, Page { page: 0 })
+            .take(3)
+            .collect()
+            .await;
+        assert_eq!(dogs.len(), 3);
+        assert_eq!(client.transport().requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stream_ends_after_error() {
+        let client =
+            Client::with_transport("https://happydog.org", transport(1))
+                .unwrap();
+        let dogs: Vec<_> =
+            client.paginate(ListDogs, Page { page: 1 }).collect().await;
+        assert_eq!(dogs.len(), 1);
+        assert!(matches!(dogs[0], Err(ClientError::StatusError(_))));
+    }
+}
@@ -0,0 +1,174 @@
+//! Composition of the action url from the `Client` address and the
+//! action path.
+//!
+//! Action path is always appended to the base path, so both conventions
+//! of the backends work and give the same result:
+//! ```rust
+//! use airactions::path::join;
+//! use airactions::Url;
+//!
+//! let base = Url::parse("https://securepay.tinkoff.ru/v2").unwrap();
+//! let url = join(&base, "Init").unwrap();
+//! assert_eq!(url.as_str(), "https://securepay.tinkoff.ru/v2/Init");
+//!
+//! let base = Url::parse("http://localhost:15100/api/").unwrap();
+//! let url = join(&base, "/session/init/payment").unwrap();
+//! assert_eq!(url.as_str(), "http://localhost:15100/api/session/init/payment");
+//! ```
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use url::Url;
+
+use crate::ClientError;
+
+/// Characters, which can't appear in a single path segment as is.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Append `path` to the path of the `base` url.
+///
+/// Leading and trailing slashes of both parts are normalized, so there
+/// is exactly one slash between segments. Trailing slash of the `path`
+/// is kept. Query of the `base` url is kept, `path` should not contain
+/// a query, use `ApiAction::query` instead.
+pub fn join(base: &Url, path: &str) -> Result<Url, url::ParseError> {
+    if base.cannot_be_a_base() {
+        return Err(url::ParseError::RelativeUrlWithCannotBeABaseBase);
+    }
+    if path.trim_matches('/').is_empty() {
+        return Ok(base.clone());
+    }
+    let mut joined = String::with_capacity(base.path().len() + path.len());
+    for segment in base
+        .path()
+        .split('/')
+        .chain(path.split('/'))
+        .filter(|segment| !segment.is_empty())
+    {
+        joined.push('/');
+        joined.push_str(segment);
+    }
+    if path.ends_with('/') {
+        joined.push('/');
+    }
+    let mut url = base.clone();
+    url.set_path(&joined);
+    Ok(url)
+}
+
+/// Substitute `{name}` placeholders in the path template with
+/// percent-encoded values.
+pub fn fill(
+    template: &str,
+    params: &[(&'static str, String)],
+) -> Result<String, ClientError> {
+    let mut path = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        let name = &rest[start + 1..end];
+        let (_, value) = params
+            .iter()
+            .find(|(param, _)| param.eq(&name))
+            .ok_or_else(|| ClientError::MissingPathParam(name.to_string()))?;
+        path.push_str(&rest[..start]);
+        path.extend(utf8_percent_encode(value, PATH_SEGMENT));
+        rest = &rest[end + 1..];
+    }
+    path.push_str(rest);
+    Ok(path)
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::{fill, join};
+    use crate::ClientError;
+
+    fn joined(base: &str, path: &str) -> String {
+        join(&Url::parse(base).unwrap(), path).unwrap().to_string()
+    }
+
+    #[test]
+    fn banksim_absolute_paths_keep_base_path() {
+        assert_eq!(
+            joined("http://localhost:15100", "/session/init/payment"),
+            "http://localhost:15100/session/init/payment"
+        );
+        assert_eq!(
+            joined("http://localhost:15100/api", "/token/info"),
+            "http://localhost:15100/api/token/info"
+        );
+        assert_eq!(
+            joined("http://localhost:15100/api/", "/token/info"),
+            "http://localhost:15100/api/token/info"
+        );
+    }
+
+    #[test]
+    fn tinkoff_relative_paths_keep_last_segment() {
+        assert_eq!(
+            joined("https://securepay.tinkoff.ru/v2", "Init"),
+            "https://securepay.tinkoff.ru/v2/Init"
+        );
+        assert_eq!(
+            joined("https://securepay.tinkoff.ru/v2/", "Init"),
+            "https://securepay.tinkoff.ru/v2/Init"
+        );
+    }
+
+    #[test]
+    fn slashes_are_normalized() {
+        assert_eq!(
+            joined("https://happydog.org//api//", "//pets//dogs/"),
+            "https://happydog.org/api/pets/dogs/"
+        );
+        assert_eq!(
+            joined("https://happydog.org/api?key=1", "/"),
+            "https://happydog.org/api?key=1"
+        );
+        assert_eq!(
+            joined("https://happydog.org/api?key=1", "pets"),
+            "https://happydog.org/api/pets?key=1"
+        );
+    }
+
+    #[test]
+    fn cannot_be_a_base_url_is_rejected() {
+        let base = Url::parse("mailto:dog@happydog.org").unwrap();
+        assert!(join(&base, "pets").is_err());
+    }
+
+    #[test]
+    fn template_is_filled_with_encoded_params() {
+        let path = fill(
+            "/session/{id}/items/{item}",
+            &[("item", "a/b c".to_string()), ("id", "42".to_string())],
+        )
+        .unwrap();
+        assert_eq!(path, "/session/42/items/a%2Fb%20c");
+    }
+
+    #[test]
+    fn unknown_path_param_is_an_error() {
+        match fill("session/{id}", &[]) {
+            Err(ClientError::MissingPathParam(name)) => assert_eq!(name, "id"),
+            _ => panic!("Expected missing path param error"),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use serde_json::Value;
+
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// Names of fields, which hold secrets in known backends.
+pub(crate) const DEFAULT_SECRET_FIELDS: [&str; 2] = ["Token", "Password"];
+
+/// Replace values of all fields with given names (case-insensitive)
+/// in the json tree with the `REDACTED` placeholder.
+pub(crate) fn redact_fields(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if fields.iter().any(|f| f.eq_ignore_ascii_case(key)) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_fields(value, fields);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                redact_fields(value, fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Body, prepared for logging: secret fields of json are redacted,
+/// card numbers are masked everywhere.
+pub(crate) fn redact_body(body: &[u8], fields: &[String]) -> String {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut json) => {
+            redact_fields(&mut json, fields);
+            mask_pans(&json.to_string())
+        }
+        Err(_) => mask_pans(&String::from_utf8_lossy(body)),
+    }
+}
+
+/// Mask all digit sequences, which look like card numbers (13-19 digits,
+/// valid Luhn checksum), keeping first 6 and last 4 digits.
+pub(crate) fn mask_pans(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let digits = &rest[..end];
+        if (13..=19).contains(&digits.len()) && luhn_valid(digits) {
+            result.push_str(&digits[..6]);
+            result.extend(std::iter::repeat_n('*', digits.len() - 10));
+            result.push_str(&digits[digits.len() - 4..]);
+        } else {
+            result.push_str(digits);
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn luhn_valid(digits: &str) -> bool {
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .map(|b| u32::from(b - b'0'))
+        .enumerate()
+        .map(|(i, d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use super::{mask_pans, redact_body};
+
+    #[test]
+    fn card_numbers_are_masked() {
+        assert_eq!(
+            mask_pans("card 4111111111111111, order 1234567890123"),
+            "card 411111******1111, order 1234567890123"
+        );
+    }
+
+    #[test]
+    fn json_body_is_redacted_and_masked() {
+        let body = br#"{"Token":"abc","Pan":"5555555555554444","Amount":100}"#;
+        let fields = vec!["Token".to_string()];
+        assert_eq!(
+            redact_body(body, &fields),
+            r#"{"Amount":100,"Pan":"555555******4444","Token":"[REDACTED]"}"#
+        );
+    }
+}
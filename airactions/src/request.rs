@@ -0,0 +1,149 @@
+use std::future::Future;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use url::Url;
+
+use crate::middleware::{ActionContext, Next};
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::ClientError;
+
+/// Http client, given to the `ApiAction::perform_action`.
+///
+/// Headers, declared by the action, are added to every built request.
+///
+/// Every request sent with it passes through the middleware stack
+/// of the `Client`, which executes the action, and then is delivered
+/// by the `Client`'s transport.
+pub struct HttpClient<'a> {
+    next: Next<'a>,
+    ctx: &'a ActionContext,
+    headers: HeaderMap,
+}
+
+impl<'a> HttpClient<'a> {
+    pub(crate) fn new(
+        next: Next<'a>,
+        ctx: &'a ActionContext,
+        headers: HeaderMap,
+    ) -> Self {
+        HttpClient { next, ctx, headers }
+    }
+
+    /// Method, declared by the executed action.
+    pub fn method(&self) -> &Method {
+        &self.ctx.method
+    }
+
+    pub fn request(&self, method: Method, url: Url) -> RequestBuilder<'a> {
+        RequestBuilder {
+            request: Ok(HttpRequest {
+                method,
+                url,
+                headers: self.headers.clone(),
+                body: Vec::new(),
+            }),
+            next: self.next,
+            ctx: self.ctx,
+        }
+    }
+
+    pub fn get(&self, url: Url) -> RequestBuilder<'a> {
+        self.request(Method::GET, url)
+    }
+
+    pub fn post(&self, url: Url) -> RequestBuilder<'a> {
+        self.request(Method::POST, url)
+    }
+
+    pub fn put(&self, url: Url) -> RequestBuilder<'a> {
+        self.request(Method::PUT, url)
+    }
+
+    pub fn delete(&self, url: Url) -> RequestBuilder<'a> {
+        self.request(Method::DELETE, url)
+    }
+
+    /// Default implementation of json actions: send `req` as json body
+    /// (or as query string for `GET` and `HEAD`), fail on non-2xx status
+    /// and decode json response.
+    pub fn send_json<Req, Resp>(
+        &self,
+        method: Method,
+        url: Url,
+        req: &Req,
+    ) -> impl Future<Output = Result<Resp, ClientError>> + Send + 'a
+    where
+        Req: Serialize + ?Sized,
+        Resp: DeserializeOwned,
+    {
+        let builder = match method {
+            Method::GET | Method::HEAD => self.request(method, url).query(req),
+            _ => self.request(method, url).json(req),
+        };
+        async move { builder.send().await?.error_for_status()?.json() }
+    }
+}
+
+/// Builder of the `HttpRequest`, which sends built request through
+/// the middleware stack.
+pub struct RequestBuilder<'a> {
+    request: Result<HttpRequest, ClientError>,
+    next: Next<'a>,
+    ctx: &'a ActionContext,
+}
+
+impl<'a> RequestBuilder<'a> {
+    pub fn header(mut self, key: HeaderName, value: HeaderValue) -> Self {
+        if let Ok(ref mut req) = self.request {
+            req.headers.insert(key, value);
+        }
+        self
+    }
+
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Self {
+        if let Ok(ref mut req) = self.request {
+            match serde_urlencoded::to_string(query) {
+                Ok(query) if query.is_empty() => {}
+                Ok(query) => {
+                    let query = match req.url.query() {
+                        Some(existing) => format!("{existing}&{query}"),
+                        None => query,
+                    };
+                    req.url.set_query(Some(&query));
+                }
+                Err(e) => self.request = Err(ClientError::QueryError(e)),
+            }
+        }
+        self
+    }
+
+    pub fn json<T: Serialize + ?Sized>(mut self, json: &T) -> Self {
+        if let Ok(ref mut req) = self.request {
+            match serde_json::to_vec(json) {
+                Ok(body) => {
+                    req.headers.insert(
+                        CONTENT_TYPE,
+                        HeaderValue::from_static("application/json"),
+                    );
+                    req.body = body;
+                }
+                Err(e) => self.request = Err(e.into()),
+            }
+        }
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        if let Ok(ref mut req) = self.request {
+            req.body = body.into();
+        }
+        self
+    }
+
+    pub async fn send(self) -> Result<HttpResponse, ClientError> {
+        self.next.run(self.request?, self.ctx).await
+    }
+}
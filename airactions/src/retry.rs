@@ -0,0 +1,277 @@
+//! Retries with exponential backoff and jitter.
+//!
+//! Only actions, which declare themselves as `ApiAction::IDEMPOTENT`,
+//! are retried on timeouts and on `429`, `502`, `503` and `504`
+//! responses. Other `5xx`, e.g. `500`, usually mean, that the server
+//! processed the request and failed, so they are not retried (though
+//! `breaker::CircuitBreaker` counts them as failures). Non-idempotent
+//! actions are retried only when the connection was not established,
+//! so the request could not reach the server and can't be duplicated.
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use airactions::retry::RetryPolicy;
+//! use airactions::Client;
+//!
+//! let client = Client::new("https://happydog.org")
+//!     .unwrap()
+//!     .with_middleware(
+//!         RetryPolicy::new(5)
+//!             .with_initial_backoff(Duration::from_millis(200))
+//!             .with_max_backoff(Duration::from_secs(10)),
+//!     );
+//! ```
+
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::StatusCode;
+
+use crate::middleware::{ActionContext, BoxFuture, Middleware, Next};
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::ClientError;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: u32,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new(3)
+    }
+}
+
+impl RetryPolicy {
+    /// `max_attempts` includes the first attempt, so `1` means no retries.
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2,
+            jitter: true,
+        }
+    }
+    /// Delay before the first retry, `100ms` by default.
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+    /// Upper bound for the delay between attempts, `5s` by default.
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+    /// Factor, the delay grows with after each attempt, `2` by default.
+    pub fn with_multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier.max(1);
+        self
+    }
+    /// With jitter enabled (default), actual delay is a random value
+    /// between zero and the computed backoff.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Delay before the attempt with given number (starting from 1).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .multiplier
+            .checked_pow(attempt.saturating_sub(2))
+            .unwrap_or(u32::MAX);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(exp)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter && !backoff.is_zero() {
+            rand::thread_rng().gen_range(Duration::ZERO..=backoff)
+        } else {
+            backoff
+        }
+    }
+
+    fn should_retry(
+        &self,
+        result: &Result<HttpResponse, ClientError>,
+        ctx: &ActionContext,
+    ) -> bool {
+        match result {
+            Ok(response) => {
+                ctx.idempotent && is_retryable_status(response.status)
+            }
+            Err(ClientError::ReqwestError(e)) => e.is_connect(),
+            Err(ClientError::Timeout) => ctx.idempotent,
+            Err(_) => false,
+        }
+    }
+}
+
+impl Middleware for RetryPolicy {
+    fn handle<'a>(
+        &'a self,
+        req: HttpRequest,
+        ctx: &'a ActionContext,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            let mut attempt = 1;
+            loop {
+                let result = next.run(req.clone(), ctx).await;
+                if attempt >= self.max_attempts
+                    || !self.should_retry(&result, ctx)
+                {
+                    return result;
+                }
+                attempt += 1;
+                tokio::time::sleep(self.backoff(attempt)).await;
+            }
+        })
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use reqwest::StatusCode;
+    use url::Url;
+
+    use super::RetryPolicy;
+    use crate::middleware::{ActionContext, BoxFuture, Middleware, Next};
+    use crate::transport::{HttpRequest, HttpResponse};
+    use crate::{ApiAction, Client, ClientError, HttpClient};
+
+    /// Responds with `503` until called `fail_times` times.
+    struct Flaky {
+        fail_times: u32,
+        calls: Arc<AtomicU32>,
+    }
+
+    impl Middleware for Flaky {
+        fn handle<'a>(
+            &'a self,
+            _req: HttpRequest,
+            _ctx: &'a ActionContext,
+            _next: Next<'a>,
+        ) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let status = if call < self.fail_times {
+                StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                StatusCode::OK
+            };
+            Box::pin(async move { Ok(HttpResponse::new(status, "")) })
+        }
+    }
+
+    struct Read;
+    struct Write;
+
+    impl ApiAction for Read {
+        type Request = ();
+        type Response = StatusCode;
+        const IDEMPOTENT: bool = true;
+        fn url_path(&self) -> &'static str {
+            "read"
+        }
+        async fn perform_action(
+            _req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            Ok(client.get(addr).send().await?.status)
+        }
+    }
+
+    impl ApiAction for Write {
+        type Request = ();
+        type Response = StatusCode;
+        fn url_path(&self) -> &'static str {
+            "write"
+        }
+        async fn perform_action(
+            _req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            Ok(client.post(addr).send().await?.status)
+        }
+    }
+
+    fn client(max_attempts: u32, fail_times: u32) -> (Client, Arc<AtomicU32>) {
+        let calls = Arc::new(AtomicU32::new(0));
+        let client = Client::new("https://happydog.org")
+            .unwrap()
+            .with_middleware(
+                RetryPolicy::new(max_attempts)
+                    .with_initial_backoff(Duration::from_millis(1)),
+            )
+            .with_middleware(Flaky {
+                fail_times,
+                calls: calls.clone(),
+            });
+        (client, calls)
+    }
+
+    #[tokio::test]
+    async fn idempotent_action_is_retried() {
+        let (client, calls) = client(3, 2);
+        let status = client.execute(Read, ()).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_max_attempts() {
+        let (client, calls) = client(2, 5);
+        let status = client.execute(Read, ()).await.unwrap();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_action_is_not_retried() {
+        let (client, calls) = client(3, 2);
+        let status = client.execute(Write, ()).await.unwrap();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_max() {
+        let policy = RetryPolicy::new(10)
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(500))
+            .with_jitter(false);
+        assert_eq!(policy.backoff(2), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(400));
+        assert_eq!(policy.backoff(5), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+    }
+}
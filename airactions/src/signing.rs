@@ -0,0 +1,283 @@
+//! Request signing with a shared secret.
+//!
+//! Request is flattened into the map of its top-level scalar fields
+//! (nested objects, arrays and nulls are not signed), the secret is
+//! added under its own key, the map, sorted by key, is canonicalized
+//! into a string (by default values are concatenated) and hashed with
+//! SHA-256 into the lowercase hex token.
+//! ```rust
+//! use airactions::signing::RequestSigner;
+//! use secrecy::Secret;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! #[serde(rename_all = "PascalCase")]
+//! struct Refund {
+//!     payment_id: u64,
+//!     amount: u32,
+//!     token: Option<String>,
+//! }
+//!
+//! let signer = RequestSigner::new()
+//!     .with_secret("Password", &Secret::new("secret".to_string()))
+//!     .without_fields(&["Token"]);
+//! let mut refund = Refund {
+//!     payment_id: 42,
+//!     amount: 1000,
+//!     token: None,
+//! };
+//! let token = signer.sign(&refund).unwrap();
+//! refund.token = Some(token.clone());
+//! assert!(signer.verify(&refund, &token));
+//! ```
+
+use std::collections::BTreeMap;
+
+use secrecy::{ExposeSecret, Secret};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error_chain_fmt;
+
+/// Signed fields, sorted by key.
+pub type Fields = BTreeMap<String, String>;
+
+#[derive(thiserror::Error)]
+pub enum SignError {
+    #[error("Failed to serialize request")]
+    SerializationError(#[from] serde_json::Error),
+    #[error("Request is not serialized into an object")]
+    NotAnObject,
+}
+
+impl std::fmt::Debug for SignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// Rule, which turns signed fields into the string to hash.
+pub trait Canonicalization: Send + Sync + 'static {
+    fn canonicalize(&self, fields: &Fields) -> String;
+}
+
+impl<F> Canonicalization for F
+where
+    F: Fn(&Fields) -> String + Send + Sync + 'static,
+{
+    fn canonicalize(&self, fields: &Fields) -> String {
+        self(fields)
+    }
+}
+
+/// Values, sorted by key, concatenated without separators.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConcatValues;
+
+impl Canonicalization for ConcatValues {
+    fn canonicalize(&self, fields: &Fields) -> String {
+        fields.values().map(String::as_str).collect()
+    }
+}
+
+// ───── RequestSigner ────────────────────────────────────────────────────── //
+
+pub struct RequestSigner {
+    secret: Option<(String, Secret<String>)>,
+    excluded: Vec<String>,
+    canonicalization: Box<dyn Canonicalization>,
+}
+
+impl Default for RequestSigner {
+    fn default() -> Self {
+        RequestSigner::new()
+    }
+}
+
+impl RequestSigner {
+    /// Signer without secret, which concatenates values.
+    pub fn new() -> Self {
+        RequestSigner {
+            secret: None,
+            excluded: Vec::new(),
+            canonicalization: Box::new(ConcatValues),
+        }
+    }
+    /// Add the secret to the signed fields under the `key`.
+    pub fn with_secret(mut self, key: &str, secret: &Secret<String>) -> Self {
+        self.secret = Some((key.to_string(), secret.clone()));
+        self
+    }
+    /// Don't sign given fields, usually the token itself.
+    pub fn without_fields(mut self, fields: &[&str]) -> Self {
+        self.excluded.extend(fields.iter().map(|f| f.to_string()));
+        self
+    }
+    /// Replace the default `ConcatValues` rule.
+    pub fn with_canonicalization(
+        mut self,
+        canonicalization: impl Canonicalization,
+    ) -> Self {
+        self.canonicalization = Box::new(canonicalization);
+        self
+    }
+
+    /// Sign top-level scalar fields of the request.
+    pub fn sign(&self, req: &impl Serialize) -> Result<String, SignError> {
+        Ok(self.sign_fields(flatten(req)?))
+    }
+
+    /// Sign prepared fields, useful when some nested values should be
+    /// signed in a custom form.
+    pub fn sign_fields(&self, mut fields: Fields) -> String {
+        for key in self.excluded.iter() {
+            fields.remove(key);
+        }
+        if let Some((ref key, ref secret)) = self.secret {
+            fields.insert(key.clone(), secret.expose_secret().clone());
+        }
+        let canonical = self.canonicalization.canonicalize(&fields);
+        format!("{:x}", Sha256::digest(canonical))
+    }
+
+    /// Check the token in constant time.
+    pub fn verify(&self, req: &impl Serialize, token: &str) -> bool {
+        match flatten(req) {
+            Ok(fields) => self.verify_fields(fields, token),
+            Err(_) => false,
+        }
+    }
+
+    /// Check the token of prepared fields in constant time.
+    pub fn verify_fields(&self, fields: Fields, token: &str) -> bool {
+        constant_time_eq(self.sign_fields(fields).as_bytes(), token.as_bytes())
+    }
+}
+
+impl std::fmt::Debug for RequestSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestSigner")
+            .field("secret", &self.secret)
+            .field("excluded", &self.excluded)
+            .finish()
+    }
+}
+
+/// Top-level scalar fields of the request as strings.
+pub fn flatten(req: &impl Serialize) -> Result<Fields, SignError> {
+    let Value::Object(object) = serde_json::to_value(req)? else {
+        return Err(SignError::NotAnObject);
+    };
+    let fields = object
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            Value::String(s) => Some((key, s)),
+            Value::Number(n) => Some((key, n.to_string())),
+            Value::Bool(b) => Some((key, b.to_string())),
+            Value::Null | Value::Array(_) | Value::Object(_) => None,
+        })
+        .collect();
+    Ok(fields)
+}
+
+/// Compare without early return, so timing doesn't reveal the position
+/// of the first mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+    use serde::Serialize;
+
+    use super::{flatten, Fields, RequestSigner, SignError};
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Init {
+        terminal_key: String,
+        amount: u32,
+        description: Option<String>,
+        receipt: Vec<String>,
+        token: String,
+    }
+
+    fn init() -> Init {
+        Init {
+            terminal_key: "TinkoffBankTest".to_string(),
+            amount: 19200,
+            description: Some("Подарочная карта на 1000 рублей".to_string()),
+            receipt: vec!["ignored".to_string()],
+            token: String::new(),
+        }
+    }
+
+    fn signer() -> RequestSigner {
+        RequestSigner::new()
+            .with_secret("Password", &Secret::new("usaf8fw8fsw21g".to_string()))
+            .without_fields(&["Token"])
+    }
+
+    #[test]
+    fn token_is_hash_of_values_sorted_by_key() {
+        // Amount, Description, Password, TerminalKey
+        let expected = {
+            use sha2::{Digest, Sha256};
+            let concatenated = "19200Подарочная карта на 1000 рублей\
+                usaf8fw8fsw21gTinkoffBankTest";
+            format!("{:x}", Sha256::digest(concatenated))
+        };
+        assert_eq!(signer().sign(&init()).unwrap(), expected);
+    }
+
+    #[test]
+    fn nested_values_and_nulls_are_not_signed() {
+        let mut req = init();
+        req.description = None;
+        let fields = flatten(&req).unwrap();
+        let keys: Vec<_> = fields.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["Amount", "TerminalKey", "Token"]);
+    }
+
+    #[test]
+    fn verify_rejects_modified_request() {
+        let mut req = init();
+        req.token = signer().sign(&req).unwrap();
+        assert!(signer().verify(&req, &req.token));
+        req.amount += 1;
+        assert!(!signer().verify(&req, &req.token));
+        assert!(!signer().verify(&req, "short"));
+    }
+
+    #[test]
+    fn canonicalization_is_pluggable() {
+        let signer =
+            RequestSigner::new().with_canonicalization(|f: &Fields| {
+                f.iter()
+                    .map(|(k, v)| format!("{k}={v}"))
+                    .collect::<Vec<_>>()
+                    .join("&")
+            });
+        let mut fields = Fields::new();
+        fields.insert("a".to_string(), "1".to_string());
+        fields.insert("b".to_string(), "2".to_string());
+        let expected = {
+            use sha2::{Digest, Sha256};
+            format!("{:x}", Sha256::digest("a=1&b=2"))
+        };
+        assert_eq!(signer.sign_fields(fields), expected);
+    }
+
+    #[test]
+    fn only_objects_can_be_signed() {
+        let response = RequestSigner::new().sign(&[1, 2, 3]);
+        assert!(matches!(response, Err(SignError::NotAnObject)));
+    }
+}
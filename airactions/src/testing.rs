@@ -0,0 +1,437 @@
+//! Local mock server for testing `ApiAction` implementations, enabled
+//! with the `testing` feature.
+//!
+//! `MockServer` listens on an ephemeral port of `127.0.0.1`, answers
+//! requests with registered `Mock`s and checks, that every mock received
+//! expected number of calls and there were no unexpected calls.
+//! ```rust
+//! use airactions::testing::{Mock, MockServer};
+//! use airactions::transport::HttpResponse;
+//! use airactions::{Client, Method, StatusCode};
+//!
+//! # async fn run() {
+//! let server = MockServer::start().await;
+//! server.mock(
+//!     Mock::given(Method::POST, "/SayHello")
+//!         .with_json_body(serde_json::json!({ "name": "Dog" }))
+//!         .respond_with(HttpResponse::new(StatusCode::OK, "Hello, Dog!"))
+//!         .expect(1),
+//! );
+//! let client = Client::new(server.url()).unwrap();
+//! // ... execute actions ...
+//! server.verify();
+//! # }
+//! ```
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use reqwest::{Method, StatusCode};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::transport::{HttpRequest, HttpResponse};
+
+type Matcher = Box<dyn Fn(&HttpRequest) -> bool + Send + Sync>;
+
+// ───── Mock ─────────────────────────────────────────────────────────────── //
+
+/// Expectation of requests with given method and path, and the response
+/// to them.
+pub struct Mock {
+    method: Method,
+    path: String,
+    matchers: Vec<Matcher>,
+    response: HttpResponse,
+    delay: Duration,
+    expected: Option<u32>,
+    calls: u32,
+}
+
+impl Mock {
+    /// Mock, which answers with empty `200 OK` and expects at least
+    /// one call.
+    pub fn given(method: Method, path: &str) -> Self {
+        Mock {
+            method,
+            path: path.to_string(),
+            matchers: Vec::new(),
+            response: HttpResponse::new(StatusCode::OK, Vec::new()),
+            delay: Duration::ZERO,
+            expected: None,
+            calls: 0,
+        }
+    }
+
+    /// Match requests, which json body contains all fields of the
+    /// `expected` value (recursively), other fields are ignored.
+    pub fn with_json_body(self, expected: Value) -> Self {
+        self.matching(move |req| {
+            serde_json::from_slice::<Value>(&req.body)
+                .map(|body| json_contains(&body, &expected))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Match requests with the custom predicate.
+    pub fn matching<F>(mut self, matcher: F) -> Self
+    where
+        F: Fn(&HttpRequest) -> bool + Send + Sync + 'static,
+    {
+        self.matchers.push(Box::new(matcher));
+        self
+    }
+
+    pub fn respond_with(mut self, response: HttpResponse) -> Self {
+        self.response = response;
+        self
+    }
+
+    /// Respond with json body.
+    ///
+    /// # Panics
+    ///
+    /// If the body can't be serialized.
+    pub fn respond_with_json<T: serde::Serialize + ?Sized>(
+        self,
+        status: StatusCode,
+        body: &T,
+    ) -> Self {
+        let response = HttpResponse::from_json(status, body)
+            .expect("Failed to serialize mock response");
+        self.respond_with(response)
+    }
+
+    /// Wait before responding, e.g. to test timeouts.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Expect exactly `times` calls, extra calls are answered
+    /// as unexpected.
+    pub fn expect(mut self, times: u32) -> Self {
+        self.expected = Some(times);
+        self
+    }
+
+    fn matches(&self, req: &HttpRequest) -> bool {
+        self.method == req.method
+            && self.path == req.url.path()
+            && self.expected.is_none_or(|expected| self.calls < expected)
+            && self.matchers.iter().all(|matcher| matcher(req))
+    }
+
+    fn is_satisfied(&self) -> bool {
+        match self.expected {
+            Some(expected) => self.calls == expected,
+            None => self.calls > 0,
+        }
+    }
+}
+
+impl std::fmt::Debug for Mock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mock")
+            .field("method", &self.method)
+            .field("path", &self.path)
+            .field("matchers", &self.matchers.len())
+            .field("expected", &self.expected)
+            .field("calls", &self.calls)
+            .finish()
+    }
+}
+
+fn json_contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            expected.iter().all(|(key, expected)| {
+                actual
+                    .get(key)
+                    .is_some_and(|actual| json_contains(actual, expected))
+            })
+        }
+        _ => actual == expected,
+    }
+}
+
+// ───── MockServer ───────────────────────────────────────────────────────── //
+
+#[derive(Debug, Default)]
+struct State {
+    mocks: Vec<Mock>,
+    received: Vec<HttpRequest>,
+    unexpected: Vec<HttpRequest>,
+}
+
+/// Http server, answering with registered mocks. Requests, which match
+/// no mock, are answered with `404 Not Found` and fail the verification.
+///
+/// The server is verified on drop, unless the thread is already
+/// panicking, and is stopped after that.
+#[derive(Debug)]
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<Mutex<State>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start server in the current tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// If there is no free port or it is called outside of the runtime.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind mock server");
+        let address = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(State::default()));
+        let handle = tokio::spawn(serve(listener, address, state.clone()));
+        MockServer {
+            address,
+            state,
+            handle,
+        }
+    }
+
+    /// Base url of the server, pass it to the `Client`.
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}/", self.address)).unwrap()
+    }
+
+    pub fn mock(&self, mock: Mock) {
+        self.state.lock().unwrap().mocks.push(mock);
+    }
+
+    /// All requests, received by the server, in order.
+    pub fn received_requests(&self) -> Vec<HttpRequest> {
+        self.state.lock().unwrap().received.clone()
+    }
+
+    /// Check, that all mocks received expected calls and there were
+    /// no unexpected calls.
+    ///
+    /// # Panics
+    ///
+    /// With the description of all violations.
+    pub fn verify(&self) {
+        let state = self.state.lock().unwrap();
+        let mut errors = Vec::new();
+        for mock in state.mocks.iter().filter(|m| !m.is_satisfied()) {
+            let expected = match mock.expected {
+                Some(expected) => expected.to_string(),
+                None => "at least 1".to_string(),
+            };
+            errors.push(format!(
+                "{} {}: expected {expected} calls, received {}",
+                mock.method, mock.path, mock.calls
+            ));
+        }
+        for req in state.unexpected.iter() {
+            errors.push(format!(
+                "Unexpected request: {} {}\n{}",
+                req.method,
+                req.url,
+                String::from_utf8_lossy(&req.body)
+            ));
+        }
+        if !errors.is_empty() {
+            panic!("Mock server verification failed:\n{}", errors.join("\n"));
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+        if !std::thread::panicking() {
+            self.verify();
+        }
+    }
+}
+
+async fn serve(
+    listener: TcpListener,
+    address: SocketAddr,
+    state: Arc<Mutex<State>>,
+) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let state = state.clone();
+        tokio::spawn(async move {
+            let service =
+                service_fn(move |req| respond(req, address, state.clone()));
+            let _ = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+async fn respond(
+    req: hyper::Request<Incoming>,
+    address: SocketAddr,
+    state: Arc<Mutex<State>>,
+) -> Result<hyper::Response<Full<Bytes>>, Infallible> {
+    let (parts, body) = req.into_parts();
+    let body = match body.collect().await {
+        Ok(body) => body.to_bytes().to_vec(),
+        Err(_) => Vec::new(),
+    };
+    let url = Url::parse(&format!("http://{address}{}", parts.uri))
+        .expect("Failed to parse request uri");
+    let req = HttpRequest {
+        method: parts.method,
+        url,
+        headers: parts.headers,
+        body,
+    };
+
+    let found = {
+        let mut state = state.lock().unwrap();
+        state.received.push(req.clone());
+        match state.mocks.iter_mut().find(|mock| mock.matches(&req)) {
+            Some(mock) => {
+                mock.calls += 1;
+                Some((mock.response.clone(), mock.delay))
+            }
+            None => {
+                state.unexpected.push(req);
+                None
+            }
+        }
+    };
+    let response = match found {
+        Some((response, delay)) => {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            response
+        }
+        None => HttpResponse::new(StatusCode::NOT_FOUND, Vec::new()),
+    };
+
+    let mut builder = hyper::Response::builder().status(response.status);
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers;
+    }
+    Ok(builder.body(Full::new(Bytes::from(response.body))).unwrap())
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Serialize};
+    use url::Url;
+
+    use super::{Mock, MockServer};
+    use crate::{ApiAction, Client, ClientError, ExecuteOptions, HttpClient};
+    use crate::{Method, StatusCode};
+
+    #[derive(Serialize, Deserialize)]
+    struct Greeting {
+        name: String,
+        mood: String,
+    }
+
+    struct Greet;
+
+    impl ApiAction for Greet {
+        type Request = Greeting;
+        type Response = Greeting;
+        fn url_path(&self) -> &'static str {
+            "/greet"
+        }
+        async fn perform_action(
+            req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client.send_json(Method::POST, addr, &req).await
+        }
+    }
+
+    fn greeting(name: &str) -> Greeting {
+        Greeting {
+            name: name.to_string(),
+            mood: "happy".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn mock_server_answers_matching_requests() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::given(Method::POST, "/greet")
+                .with_json_body(serde_json::json!({ "name": "Dog" }))
+                .respond_with_json(StatusCode::OK, &greeting("Hello, Dog!"))
+                .expect(1),
+        );
+        let client = Client::new(server.url()).unwrap();
+        let response = client.execute(Greet, greeting("Dog")).await.unwrap();
+        assert_eq!(response.name, "Hello, Dog!");
+        assert_eq!(
+            server.received_requests()[0].headers["content-type"],
+            "application/json"
+        );
+        server.verify();
+    }
+
+    #[tokio::test]
+    async fn delayed_response_triggers_timeout() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::given(Method::POST, "/greet")
+                .respond_with_json(StatusCode::OK, &greeting("Hello!"))
+                .with_delay(Duration::from_millis(500)),
+        );
+        let client = Client::new(server.url()).unwrap();
+        let options =
+            ExecuteOptions::new().with_timeout(Duration::from_millis(50));
+        let response =
+            client.execute_with(Greet, greeting("Dog"), options).await;
+        assert!(matches!(response, Err(ClientError::Timeout)));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Unexpected request: POST")]
+    async fn unexpected_request_fails_verification() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::given(Method::POST, "/greet")
+                .with_json_body(serde_json::json!({ "name": "Cat" })),
+        );
+        let client = Client::new(server.url()).unwrap();
+        let response = client.execute(Greet, greeting("Dog")).await;
+        assert!(matches!(response, Err(ClientError::StatusError(_))));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "expected 2 calls, received 1")]
+    async fn unmet_expectation_fails_verification() {
+        let server = MockServer::start().await;
+        server.mock(
+            Mock::given(Method::POST, "/greet")
+                .respond_with_json(StatusCode::OK, &greeting("Hello!"))
+                .expect(2),
+        );
+        let client = Client::new(server.url()).unwrap();
+        client.execute(Greet, greeting("Dog")).await.unwrap();
+    }
+}
@@ -0,0 +1,269 @@
+//! Tracing of executed actions.
+//!
+//! Every `Client::execute` call is wrapped in the `airactions.execute`
+//! span with `action`, `url_path`, `method`, `attempts`, `status`,
+//! `latency_ms` and `error` fields. Every transport call is wrapped in
+//! the nested `airactions.attempt` span.
+//!
+//! Request and response bodies are logged only when `TRACE` level is
+//! enabled for the `airactions::body` target, with secret fields redacted
+//! and card numbers masked.
+
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use tracing::field::Empty;
+use tracing::{Instrument, Level, Span};
+
+use crate::middleware::{ActionContext, BoxFuture};
+use crate::redact::{redact_body, DEFAULT_SECRET_FIELDS};
+use crate::transport::{HttpRequest, HttpResponse, Transport};
+use crate::{ClientError, Method};
+
+const BODY_TARGET: &str = "airactions::body";
+
+pub(crate) fn action_span(
+    action: &'static str,
+    url_path: &'static str,
+    method: &Method,
+) -> Span {
+    tracing::info_span!(
+        "airactions.execute",
+        action,
+        url_path,
+        method = %method,
+        attempts = Empty,
+        status = Empty,
+        latency_ms = Empty,
+        error = Empty,
+    )
+}
+
+/// Record the outcome of the whole action.
+pub(crate) fn record_result<T>(
+    span: &Span,
+    started: Instant,
+    result: &Result<T, ClientError>,
+) {
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    match result {
+        Ok(_) => tracing::debug!(parent: span, "Action succeeded"),
+        Err(e) => {
+            span.record("error", tracing::field::display(e));
+            tracing::warn!(parent: span, error = %e, "Action failed");
+        }
+    }
+}
+
+/// Final step of the middleware stack: send request with the transport
+/// inside of the attempt span.
+pub(crate) fn send<'a>(
+    transport: &'a dyn Transport,
+    req: HttpRequest,
+    ctx: &'a ActionContext,
+) -> BoxFuture<'a, Result<HttpResponse, ClientError>> {
+    let attempt = ctx.attempts.fetch_add(1, Ordering::Relaxed) + 1;
+    ctx.span.record("attempts", attempt);
+    let span = tracing::debug_span!(
+        parent: &ctx.span,
+        "airactions.attempt",
+        attempt,
+        url = %req.url,
+        status = Empty,
+        latency_ms = Empty,
+    );
+    log_body(&span, "Request body", &req.body);
+    Box::pin(async move {
+        let started = Instant::now();
+        let result = transport.send(req).instrument(span.clone()).await;
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+        if let Ok(response) = &result {
+            span.record("status", response.status.as_u16());
+            ctx.span.record("status", response.status.as_u16());
+            log_body(&span, "Response body", &response.body);
+        }
+        result
+    })
+}
+
+fn log_body(span: &Span, message: &str, body: &[u8]) {
+    if body.is_empty() || !tracing::enabled!(target: BODY_TARGET, Level::TRACE)
+    {
+        return;
+    }
+    let fields: Vec<String> = DEFAULT_SECRET_FIELDS
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+    tracing::trace!(
+        target: BODY_TARGET,
+        parent: span,
+        body = %redact_body(body, &fields),
+        "{message}"
+    );
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::{Arc, Mutex};
+
+    use serde::{Deserialize, Serialize};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Subscriber};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+    use url::Url;
+
+    use crate::transport::{HttpResponse, InMemoryTransport};
+    use crate::{ApiAction, Client, ClientError, HttpClient};
+    use crate::{Method, StatusCode};
+
+    type Fields = BTreeMap<String, String>;
+
+    #[derive(Default)]
+    struct Captured {
+        /// Spans by id, in order of creation.
+        spans: Vec<(u64, &'static str, Fields)>,
+        /// Events of the body target.
+        bodies: Vec<Fields>,
+    }
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Captured>>);
+
+    struct Visitor<'a>(&'a mut Fields);
+
+    impl Visit for Visitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    impl<S> Layer<S> for Capture
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &Attributes<'_>,
+            id: &Id,
+            _: Context<'_, S>,
+        ) {
+            let mut fields = Fields::new();
+            attrs.record(&mut Visitor(&mut fields));
+            let name = attrs.metadata().name();
+            self.0
+                .lock()
+                .unwrap()
+                .spans
+                .push((id.into_u64(), name, fields));
+        }
+        fn on_record(&self, id: &Id, values: &Record<'_>, _: Context<'_, S>) {
+            let mut captured = self.0.lock().unwrap();
+            if let Some((_, _, fields)) = captured
+                .spans
+                .iter_mut()
+                .find(|(span, _, _)| *span == id.into_u64())
+            {
+                values.record(&mut Visitor(fields));
+            }
+        }
+        fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+            if event.metadata().target() == super::BODY_TARGET {
+                let mut fields = Fields::new();
+                event.record(&mut Visitor(&mut fields));
+                self.0.lock().unwrap().bodies.push(fields);
+            }
+        }
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Login {
+        user: String,
+        password: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Session {
+        id: u32,
+    }
+
+    struct LoginAction;
+
+    impl ApiAction for LoginAction {
+        type Request = Login;
+        type Response = Session;
+        fn url_path(&self) -> &'static str {
+            "/login"
+        }
+        async fn perform_action(
+            req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client
+                .post(addr)
+                .json(&req)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+        }
+    }
+
+    #[tokio::test]
+    async fn spans_have_fields_and_bodies_are_redacted() {
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let transport =
+            InMemoryTransport::new().route(Method::POST, "/login", |_| {
+                let body = serde_json::json!({ "id": 7 });
+                HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+            });
+        let client =
+            Client::with_transport("https://happydog.org", transport).unwrap();
+        let login = Login {
+            user: "rex".to_string(),
+            password: "bone".to_string(),
+        };
+        let session = client.execute(LoginAction, login).await.unwrap();
+        assert_eq!(session.id, 7);
+
+        let captured = capture.0.lock().unwrap();
+        let spans: HashMap<_, _> = captured
+            .spans
+            .iter()
+            .map(|(_, name, fields)| (*name, fields))
+            .collect();
+        let execute = spans["airactions.execute"];
+        assert!(execute["action"].ends_with("LoginAction"));
+        assert_eq!(execute["url_path"], "/login");
+        assert_eq!(execute["method"], "POST");
+        assert_eq!(execute["attempts"], "1");
+        assert_eq!(execute["status"], "200");
+        assert!(execute.contains_key("latency_ms"));
+        assert!(!execute.contains_key("error"));
+        let attempt = spans["airactions.attempt"];
+        assert_eq!(attempt["attempt"], "1");
+        assert_eq!(attempt["url"], "https://happydog.org/login");
+        assert_eq!(attempt["status"], "200");
+
+        assert_eq!(captured.bodies.len(), 2);
+        let request = &captured.bodies[0]["body"];
+        assert!(request.contains("rex"));
+        assert!(request.contains("[REDACTED]"));
+        assert!(!request.contains("bone"));
+    }
+}
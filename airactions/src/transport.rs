@@ -0,0 +1,320 @@
+//! Transport layer, which actually delivers prepared requests.
+//!
+//! `Client` is generic over the `Transport`, `ReqwestTransport` is used
+//! by default. `InMemoryTransport` allows to test actions without network:
+//! ```rust
+//! use airactions::transport::{HttpResponse, InMemoryTransport};
+//! use airactions::{Client, Method, StatusCode};
+//!
+//! let transport = InMemoryTransport::new().route(
+//!     Method::POST,
+//!     "/SayHello",
+//!     |_req| HttpResponse::new(StatusCode::OK, "Hello!"),
+//! );
+//! let client =
+//!     Client::with_transport("https://happydog.org", transport).unwrap();
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use url::Url;
+
+use crate::middleware::BoxFuture;
+use crate::{ClientError, ReqwestClient};
+
+// ───── Request & Response ───────────────────────────────────────────────── //
+
+/// Prepared request, ready to be sent with any `Transport`.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+/// Fully received response.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        HttpResponse {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Create response with serialized json body.
+    pub fn from_json<T: Serialize + ?Sized>(
+        status: StatusCode,
+        body: &T,
+    ) -> Result<Self, ClientError> {
+        let mut response = HttpResponse::new(status, serde_json::to_vec(body)?);
+        response.headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            "application/json".parse().unwrap(),
+        );
+        Ok(response)
+    }
+
+    /// Body as a string, invalid utf-8 sequences are replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    /// Turn non-2xx response into the `ClientError::StatusError`.
+    pub fn error_for_status(self) -> Result<Self, ClientError> {
+        if self.is_success() {
+            Ok(self)
+        } else {
+            Err(ClientError::StatusError(Box::new(self)))
+        }
+    }
+
+    /// Decode json body, on failure the error keeps the offending body.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, ClientError> {
+        serde_json::from_slice(&self.body).map_err(|e| {
+            ClientError::DecodeError {
+                source: e,
+                body: self.text(),
+            }
+        })
+    }
+}
+
+// ───── Transport ────────────────────────────────────────────────────────── //
+
+pub trait Transport: Send + Sync + 'static {
+    fn send(
+        &self,
+        req: HttpRequest,
+    ) -> BoxFuture<'_, Result<HttpResponse, ClientError>>;
+}
+
+/// Default transport, backed by `reqwest::Client`.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: ReqwestClient,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: ReqwestClient) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(
+        &self,
+        req: HttpRequest,
+    ) -> BoxFuture<'_, Result<HttpResponse, ClientError>> {
+        Box::pin(async move {
+            let mut builder = self
+                .client
+                .request(req.method, req.url)
+                .headers(req.headers);
+            if !req.body.is_empty() {
+                builder = builder.body(req.body);
+            }
+            let response = builder.send().await.map_err(timeout_or_else)?;
+            Ok(HttpResponse {
+                status: response.status(),
+                headers: response.headers().clone(),
+                body: response.bytes().await.map_err(timeout_or_else)?.to_vec(),
+            })
+        })
+    }
+}
+
+fn timeout_or_else(e: reqwest::Error) -> ClientError {
+    if e.is_timeout() {
+        ClientError::Timeout
+    } else {
+        ClientError::ReqwestError(e)
+    }
+}
+
+type Handler = Arc<dyn Fn(&HttpRequest) -> HttpResponse + Send + Sync>;
+
+/// Transport, which answers requests with registered handlers instead of
+/// sending them over the network. Requests to unknown routes are
+/// answered with `404 Not Found`.
+///
+/// Cloned instances share routes and received requests.
+#[derive(Clone, Default)]
+pub struct InMemoryTransport {
+    routes: Arc<Mutex<Vec<(Method, String, Handler)>>>,
+    received: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl InMemoryTransport {
+    pub fn new() -> Self {
+        InMemoryTransport::default()
+    }
+
+    /// Register handler for requests with given method and url path.
+    pub fn route<F>(self, method: Method, path: &str, handler: F) -> Self
+    where
+        F: Fn(&HttpRequest) -> HttpResponse + Send + Sync + 'static,
+    {
+        self.routes.lock().unwrap().push((
+            method,
+            path.to_string(),
+            Arc::new(handler),
+        ));
+        self
+    }
+
+    /// All requests, received by this transport, in order.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+impl Transport for InMemoryTransport {
+    fn send(
+        &self,
+        req: HttpRequest,
+    ) -> BoxFuture<'_, Result<HttpResponse, ClientError>> {
+        let handler = self
+            .routes
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(method, path, _)| {
+                method.eq(&req.method) && path.eq(req.url.path())
+            })
+            .map(|(_, _, handler)| handler.clone());
+        let response = match handler {
+            Some(handler) => handler(&req),
+            None => HttpResponse::new(StatusCode::NOT_FOUND, Vec::new()),
+        };
+        self.received.lock().unwrap().push(req);
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+impl std::fmt::Debug for InMemoryTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemoryTransport")
+            .field("routes", &self.routes.lock().unwrap().len())
+            .field("received", &self.received.lock().unwrap().len())
+            .finish()
+    }
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use url::Url;
+
+    use super::{HttpResponse, InMemoryTransport};
+    use crate::{ApiAction, Client, ClientError, HttpClient};
+    use crate::{Method, StatusCode};
+
+    #[derive(Serialize, Deserialize)]
+    struct Greeting {
+        name: String,
+    }
+
+    struct Greet;
+
+    impl ApiAction for Greet {
+        type Request = Greeting;
+        type Response = Greeting;
+        fn url_path(&self) -> &'static str {
+            "/greet"
+        }
+        async fn perform_action(
+            req: Self::Request,
+            addr: Url,
+            client: &HttpClient<'_>,
+        ) -> Result<Self::Response, ClientError> {
+            client
+                .post(addr)
+                .json(&req)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+        }
+    }
+
+    #[tokio::test]
+    async fn in_memory_transport_answers_with_registered_route() {
+        let transport =
+            InMemoryTransport::new().route(Method::POST, "/greet", |req| {
+                let greeting: Greeting = serde_json::from_slice(&req.body)
+                    .expect("Failed to parse request");
+                let greeting = Greeting {
+                    name: format!("Hello, {}!", greeting.name),
+                };
+                HttpResponse::from_json(StatusCode::OK, &greeting).unwrap()
+            });
+        let client =
+            Client::with_transport("https://happydog.org", transport.clone())
+                .unwrap();
+        let request = Greeting {
+            name: "Dog".to_string(),
+        };
+        let response = client.execute(Greet, request).await.unwrap();
+        assert_eq!(response.name, "Hello, Dog!");
+
+        let received = transport.requests();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].headers["content-type"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn in_memory_transport_answers_not_found_for_unknown_route() {
+        let transport = InMemoryTransport::new();
+        let client =
+            Client::with_transport("https://happydog.org", transport).unwrap();
+        let request = Greeting {
+            name: "Dog".to_string(),
+        };
+        let response = client.execute(Greet, request).await;
+        match response {
+            Err(ClientError::StatusError(response)) => {
+                assert_eq!(response.status, StatusCode::NOT_FOUND)
+            }
+            _ => panic!("Expected status error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn html_error_page_keeps_offending_body() {
+        let transport =
+            InMemoryTransport::new().route(Method::POST, "/greet", |_| {
+                HttpResponse::new(StatusCode::OK, "<html>Oops</html>")
+            });
+        let client =
+            Client::with_transport("https://happydog.org", transport).unwrap();
+        let request = Greeting {
+            name: "Dog".to_string(),
+        };
+        let response = client.execute(Greet, request).await;
+        match response {
+            Err(ClientError::DecodeError { body, .. }) => {
+                assert_eq!(body, "<html>Oops</html>")
+            }
+            _ => panic!("Expected decode error"),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use airactions::transport::{HttpResponse, InMemoryTransport};
+use airactions::{ApiAction, Client, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Greeting {
+    name: String,
+}
+
+#[derive(ApiAction)]
+#[action(path = "/greet", request = Greeting, response = Greeting)]
+struct Greet;
+
+#[derive(ApiAction)]
+#[action(
+    method = "GET",
+    request = Greeting,
+    response = Greeting,
+    idempotent,
+    cache_ttl_secs = 30
+)]
+enum Lookup {
+    #[action(path = "/dogs")]
+    Dog,
+    #[action(path = "/cats")]
+    Cat,
+}
+
+#[derive(Serialize)]
+struct Pet {
+    id: u32,
+    name: String,
+}
+
+#[derive(ApiAction)]
+#[action(
+    path = "/pets/{id}",
+    method = "put",
+    header("X-Api-Version", "2"),
+    idempotency_key = "id",
+    request = Pet,
+    response = Greeting
+)]
+struct RenamePet;
+
+fn is_idempotent<A: ApiAction>(_: &A) -> bool {
+    A::IDEMPOTENT
+}
+
+fn echo(prefix: &'static str) -> impl Fn(&str) -> HttpResponse {
+    move |name| {
+        let greeting = Greeting {
+            name: format!("{prefix}, {name}!"),
+        };
+        HttpResponse::from_json(StatusCode::OK, &greeting).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn derived_action_sends_json_body() {
+    let hello = echo("Hello");
+    let transport =
+        InMemoryTransport::new().route(Method::POST, "/greet", move |req| {
+            let greeting: Greeting = serde_json::from_slice(&req.body)
+                .expect("Failed to parse request");
+            hello(&greeting.name)
+        });
+    let client =
+        Client::with_transport("https://happydog.org", transport.clone())
+            .unwrap();
+    let request = Greeting {
+        name: "Dog".to_string(),
+    };
+    let response = client.execute(Greet, request).await.unwrap();
+    assert_eq!(response.name, "Hello, Dog!");
+    assert!(!is_idempotent(&Greet));
+}
+
+#[tokio::test]
+async fn derived_enum_action_uses_variant_path_and_query() {
+    let found = echo("Found");
+    let transport =
+        InMemoryTransport::new().route(Method::GET, "/cats", move |req| {
+            assert!(req.body.is_empty());
+            found(req.url.query().unwrap())
+        });
+    let client =
+        Client::with_transport("https://happydog.org", transport).unwrap();
+    let request = Greeting {
+        name: "Tom".to_string(),
+    };
+    let response = client.execute(Lookup::Cat, request).await.unwrap();
+    assert_eq!(response.name, "Found, name=Tom!");
+    assert_eq!(Lookup::Dog.url_path(), "/dogs");
+    assert!(is_idempotent(&Lookup::Dog));
+    assert_eq!(Lookup::Dog.cache_ttl(), Some(Duration::from_secs(30)));
+    assert_eq!(Greet.cache_ttl(), None);
+}
+
+#[tokio::test]
+async fn derived_action_fills_path_params_and_headers() {
+    let renamed = echo("Renamed");
+    let transport =
+        InMemoryTransport::new().route(Method::PUT, "/pets/7", move |req| {
+            assert_eq!(req.headers["x-api-version"], "2");
+            renamed("Rex")
+        });
+    let client =
+        Client::with_transport("https://happydog.org", transport).unwrap();
+    let request = Pet {
+        id: 7,
+        name: "Rex".to_string(),
+    };
+    assert_eq!(RenamePet.idempotency_key(&request).as_deref(), Some("7"));
+    let response = client.execute(RenamePet, request).await.unwrap();
+    assert_eq!(response.name, "Renamed, Rex!");
+    assert_eq!(
+        Greet.idempotency_key(&Greeting { name: "Dog".into() }),
+        None
+    );
+}
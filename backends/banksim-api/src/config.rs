@@ -0,0 +1,40 @@
+//! Profiles of the banksim instances.
+//!
+//! ```rust,no_run
+//! use banksim_api::config::BanksimConfig;
+//!
+//! // BANKSIM_ENVIRONMENT=local BANKSIM_PASSWORD=...
+//! let config = BanksimConfig::from_env().unwrap();
+//! ```
+
+use airactions::config::{ConfigError, Preset, Profile};
+use airactions::Client;
+use secrecy::Secret;
+
+/// Banksim, running locally with default settings.
+pub const LOCAL: Preset = Preset::new("local", "http://localhost:15100");
+pub const PRESETS: &[Preset] = &[LOCAL];
+
+pub const ENV_PREFIX: &str = "BANKSIM";
+
+/// Client and cashbox credentials.
+#[derive(Debug)]
+pub struct BanksimConfig {
+    pub client: Client,
+    /// Used to generate request tokens.
+    pub cashbox_password: Secret<String>,
+}
+
+impl BanksimConfig {
+    pub fn from_profile(profile: &Profile) -> Result<Self, ConfigError> {
+        Ok(BanksimConfig {
+            client: profile.client(PRESETS)?,
+            cashbox_password: profile.password.clone(),
+        })
+    }
+
+    /// Load the profile from `BANKSIM_*` environment variables.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        BanksimConfig::from_profile(&Profile::from_env(ENV_PREFIX)?)
+    }
+}
@@ -1,9 +1,7 @@
-use std::collections::BTreeMap;
-
-use airactions::{ApiAction, ClientError, ReqwestClient};
-use secrecy::{ExposeSecret, Secret};
+use airactions::signing::{self, Fields};
+use airactions::ApiAction;
+use secrecy::Secret;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use url::Url;
 use uuid::Uuid;
 
@@ -15,27 +13,14 @@ pub mod beneficiaries;
 
 // ───── Api Action ───────────────────────────────────────────────────────── //
 
+#[derive(ApiAction)]
+#[action(
+    path = "/session/init/payment",
+    request = InitPaymentRequest,
+    response = InitPaymentResponse
+)]
 pub struct InitPayment;
 
-impl ApiAction for InitPayment {
-    type Request = InitPaymentRequest;
-    type Response = InitPaymentResponse;
-
-    fn url_path(&self) -> &'static str {
-        "/session/init/payment"
-    }
-    async fn perform_action(
-        req: Self::Request,
-        addr: Url,
-        client: &ReqwestClient,
-    ) -> Result<Self::Response, ClientError> {
-        match client.post(addr).json(&req).send().await {
-            Ok(response) => Ok(response.json().await?),
-            Err(e) => Err(e)?,
-        }
-    }
-}
-
 // ───── Request Type ─────────────────────────────────────────────────────── //
 
 /// Initial payment operation
@@ -76,31 +61,27 @@ impl InitPaymentRequest {
         req
     }
     pub fn generate_token(&self, cashbox_password: &Secret<String>) -> String {
-        let mut token_map = BTreeMap::new();
-        token_map.insert("notification_url", self.notification_url.to_string());
-        token_map.insert("success_url", self.success_url.to_string());
-        token_map.insert("fail_url", self.fail_url.to_string());
-        token_map.insert("amount", self.amount.to_string());
-        token_map.insert("password", cashbox_password.expose_secret().clone());
-
+        crate::signer(cashbox_password).sign_fields(self.signed_fields())
+    }
+    /// Beneficiaries are signed as concatenated card tokens.
+    fn signed_fields(&self) -> Fields {
+        let mut fields = signing::flatten(self)
+            .expect("Request is serialized into an object");
         if !self.beneficiaries.is_empty() {
-            token_map.insert("beneficiaries", self.beneficiaries.as_str());
+            fields.insert(
+                "beneficiaries".to_string(),
+                self.beneficiaries.as_str(),
+            );
         }
-
-        let concatenated: String = token_map.into_values().collect();
-        let mut hasher: Sha256 = Digest::new();
-        hasher.update(concatenated);
-        let hash_result = hasher.finalize();
-
-        // Convert hash result to a hex string
-        format!("{:x}", hash_result)
+        fields
     }
 }
 
 impl Tokenizable for InitPaymentRequest {
     fn validate_token(&self, password: &Secret<String>) -> Result<(), ()> {
-        let token = self.generate_token(password);
-        if token.eq(&self.token) {
+        if crate::signer(password)
+            .verify_fields(self.signed_fields(), &self.token)
+        {
             Ok(())
         } else {
             Err(())
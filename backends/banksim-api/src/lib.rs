@@ -1,10 +1,12 @@
+#![allow(clippy::result_unit_err)]
+
 use secrecy::Secret;
 use serde::{Deserialize, Serialize};
 
 pub use airactions::*;
-use url::Url;
 use uuid::Uuid;
 
+pub mod config;
 pub mod init_payment;
 pub mod make_payment;
 pub mod notifications;
@@ -39,6 +41,14 @@ pub trait Tokenizable {
     fn validate_token(&self, password: &Secret<String>) -> Result<(), ()>;
 }
 
+/// Signer of the banksim requests: values, sorted by key, with the cashbox
+/// password under the `password` key.
+fn signer(cashbox_password: &Secret<String>) -> signing::RequestSigner {
+    signing::RequestSigner::new()
+        .with_secret("password", cashbox_password)
+        .without_fields(&["token"])
+}
+
 pub trait Operation {
     fn operation_error(reason: OperationError) -> Self;
     fn operation_success(session_ui_url: Url, session_id: Uuid) -> Self;
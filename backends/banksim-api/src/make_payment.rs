@@ -1,37 +1,19 @@
-use std::collections::BTreeMap;
-
-use airactions::{ApiAction, ClientError, ReqwestClient};
-use secrecy::{ExposeSecret, Secret};
+use airactions::ApiAction;
+use secrecy::Secret;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
-use url::Url;
 
 use crate::Tokenizable;
 
 // ───── Api Action ───────────────────────────────────────────────────────── //
 
+#[derive(ApiAction)]
+#[action(
+    path = "/api/MakePayment",
+    request = MakePaymentRequest,
+    response = MakePaymentResponse
+)]
 pub struct MakePayment;
 
-impl ApiAction for MakePayment {
-    type Request = MakePaymentRequest;
-    type Response = MakePaymentResponse;
-
-    fn url_path(&self) -> &'static str {
-        "/api/MakePayment"
-    }
-
-    async fn perform_action(
-        req: Self::Request,
-        addr: Url,
-        client: &ReqwestClient,
-    ) -> Result<Self::Response, ClientError> {
-        match client.post(addr).json(&req).send().await {
-            Ok(response) => Ok(response.json().await?),
-            Err(e) => Err(e)?,
-        }
-    }
-}
-
 // ───── Request Type ─────────────────────────────────────────────────────── //
 
 /// Initial payment operation, basic of acquiring
@@ -60,25 +42,15 @@ impl MakePaymentRequest {
     }
 
     pub fn generate_token(&self, cashbox_password: &Secret<String>) -> String {
-        let mut token_map = BTreeMap::new();
-        token_map.insert("recipient_token", self.recipient_token.clone());
-        token_map.insert("amount", self.amount.to_string());
-        token_map.insert("password", cashbox_password.expose_secret().clone());
-
-        let concatenated: String = token_map.into_values().collect();
-        let mut hasher: Sha256 = Digest::new();
-        hasher.update(concatenated);
-        let hash_result = hasher.finalize();
-
-        // Convert hash result to a hex string
-        format!("{:x}", hash_result)
+        crate::signer(cashbox_password)
+            .sign(self)
+            .expect("Request is serialized into an object")
     }
 }
 
 impl Tokenizable for MakePaymentRequest {
     fn validate_token(&self, password: &Secret<String>) -> Result<(), ()> {
-        let token = self.generate_token(password);
-        if token.eq(&self.token) {
+        if crate::signer(password).verify(self, &self.token) {
             Ok(())
         } else {
             Err(())
@@ -1,36 +1,21 @@
 use crate::{Operation, OperationError, OperationStatus, Tokenizable};
-use std::collections::BTreeMap;
 
-use airactions::{ApiAction, ClientError, ReqwestClient};
-use secrecy::{ExposeSecret, Secret};
+use airactions::ApiAction;
+use secrecy::Secret;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use url::Url;
 use uuid::Uuid;
 
 // ───── Api Action ───────────────────────────────────────────────────────── //
 
+#[derive(ApiAction)]
+#[action(
+    path = "/session/init/card_token_reg",
+    request = RegisterCardTokenRequest,
+    response = RegisterCardTokenResponse
+)]
 pub struct RegisterCardToken;
 
-impl ApiAction for RegisterCardToken {
-    type Request = RegisterCardTokenRequest;
-    type Response = RegisterCardTokenResponse;
-
-    fn url_path(&self) -> &'static str {
-        "/session/init/card_token_reg"
-    }
-    async fn perform_action(
-        req: Self::Request,
-        addr: Url,
-        client: &ReqwestClient,
-    ) -> Result<Self::Response, ClientError> {
-        match client.post(addr).json(&req).send().await {
-            Ok(response) => Ok(response.json().await?),
-            Err(e) => Err(e)?,
-        }
-    }
-}
-
 // ───── Request Type ─────────────────────────────────────────────────────── //
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -58,26 +43,15 @@ impl RegisterCardTokenRequest {
         req
     }
     pub fn generate_token(&self, cashbox_password: &Secret<String>) -> String {
-        let mut token_map = BTreeMap::new();
-        token_map.insert("notification_url", self.notification_url.to_string());
-        token_map.insert("fail_url", self.fail_url.to_string());
-        token_map.insert("success_url", self.success_url.to_string());
-        token_map.insert("password", cashbox_password.expose_secret().clone());
-
-        let concatenated: String = token_map.into_values().collect();
-        let mut hasher: Sha256 = Digest::new();
-        hasher.update(concatenated);
-        let hash_result = hasher.finalize();
-
-        // Convert hash result to a hex string
-        format!("{:x}", hash_result)
+        crate::signer(cashbox_password)
+            .sign(self)
+            .expect("Request is serialized into an object")
     }
 }
 
 impl Tokenizable for RegisterCardTokenRequest {
     fn validate_token(&self, password: &Secret<String>) -> Result<(), ()> {
-        let token = self.generate_token(password);
-        if token.eq(&self.token) {
+        if crate::signer(password).verify(self, &self.token) {
             Ok(())
         } else {
             Err(())
@@ -1,20 +1,21 @@
 use crate::OperationStatus;
 use crate::Tokenizable;
-use std::collections::BTreeMap;
 
-use airactions::{ApiAction, ClientError, ReqwestClient};
-use secrecy::{ExposeSecret, Secret};
+use airactions::ApiAction;
+use secrecy::Secret;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
-use url::Url;
 use uuid::Uuid;
 
 // ───── Api Action ───────────────────────────────────────────────────────── //
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ApiAction)]
+#[action(request = WebhookRequest, response = WebhookResponse)]
 pub enum Webhook {
+    #[action(path = "/session/confirm")]
     Confirm,
+    #[action(path = "/session/capture")]
     Capture,
+    #[action(path = "/session/cancel")]
     Cancel,
 }
 
@@ -28,30 +29,6 @@ impl std::fmt::Display for Webhook {
     }
 }
 
-impl ApiAction for Webhook {
-    type Request = WebhookRequest;
-    type Response = WebhookResponse;
-
-    fn url_path(&self) -> &'static str {
-        match self {
-            Webhook::Confirm => "/session/confirm",
-            Webhook::Capture => "/session/capture",
-            Webhook::Cancel => "/session/cancel",
-        }
-    }
-
-    async fn perform_action(
-        req: Self::Request,
-        addr: Url,
-        client: &ReqwestClient,
-    ) -> Result<Self::Response, ClientError> {
-        match client.post(addr).json(&req).send().await {
-            Ok(response) => Ok(response.json().await?),
-            Err(e) => Err(e)?,
-        }
-    }
-}
-
 // ───── Request Type ─────────────────────────────────────────────────────── //
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -71,24 +48,15 @@ impl WebhookRequest {
     }
 
     pub fn generate_token(&self, cashbox_password: &Secret<String>) -> String {
-        let mut token_map = BTreeMap::new();
-        token_map.insert("session_id", self.session_id.to_string());
-        token_map.insert("password", cashbox_password.expose_secret().clone());
-
-        let concatenated: String = token_map.into_values().collect();
-        let mut hasher: Sha256 = Digest::new();
-        hasher.update(concatenated);
-        let hash_result = hasher.finalize();
-
-        // Convert hash result to a hex string
-        format!("{:x}", hash_result)
+        crate::signer(cashbox_password)
+            .sign(self)
+            .expect("Request is serialized into an object")
     }
 }
 
 impl Tokenizable for WebhookRequest {
     fn validate_token(&self, password: &Secret<String>) -> Result<(), ()> {
-        let token = self.generate_token(password);
-        if token.eq(&self.token) {
+        if crate::signer(password).verify(self, &self.token) {
             Ok(())
         } else {
             Err(())
@@ -103,19 +71,3 @@ pub struct WebhookResponse {
     pub session_id: Uuid,
     pub status: OperationStatus,
 }
-
-// impl_request_action!(
-//     Confirm,
-//     ConfirmRequest,
-//     ConfirmResponse,
-//     "/session/confirm"
-// );
-
-// impl_request_action!(
-//     Capture,
-//     CaptureRequest,
-//     CaptureResponse,
-//     "/session/capture"
-// );
-
-// impl_request_action!(Cancel, CancelRequest, CancelResponse, "/session/cancel");
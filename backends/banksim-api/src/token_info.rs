@@ -1,35 +1,21 @@
-use crate::{OperationStatus, Tokenizable};
-use std::collections::BTreeMap;
+use crate::Tokenizable;
 
-use airactions::{ApiAction, ClientError, ReqwestClient};
-use secrecy::{ExposeSecret, Secret};
+use airactions::ApiAction;
+use secrecy::Secret;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
-use url::Url;
 
 // ───── Api Action ───────────────────────────────────────────────────────── //
 
+#[derive(ApiAction)]
+#[action(
+    path = "/token/info",
+    request = TokenInfoRequest,
+    response = TokenInfoResponse,
+    idempotent,
+    cache_ttl_secs = 30
+)]
 pub struct TokenInfo;
 
-impl ApiAction for TokenInfo {
-    type Request = TokenInfoRequest;
-    type Response = TokenInfoResponse;
-
-    fn url_path(&self) -> &'static str {
-        "/token/info"
-    }
-    async fn perform_action(
-        req: Self::Request,
-        addr: Url,
-        client: &ReqwestClient,
-    ) -> Result<Self::Response, ClientError> {
-        match client.post(addr).json(&req).send().await {
-            Ok(response) => Ok(response.json().await?),
-            Err(e) => Err(e)?,
-        }
-    }
-}
-
 // ───── Request Type ─────────────────────────────────────────────────────── //
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -48,24 +34,15 @@ impl TokenInfoRequest {
         req
     }
     pub fn generate_token(&self, cashbox_password: &Secret<String>) -> String {
-        let mut token_map = BTreeMap::new();
-        token_map.insert("card_token", self.card_token.clone());
-        token_map.insert("password", cashbox_password.expose_secret().clone());
-
-        let concatenated: String = token_map.into_values().collect();
-        let mut hasher: Sha256 = Digest::new();
-        hasher.update(concatenated);
-        let hash_result = hasher.finalize();
-
-        // Convert hash result to a hex string
-        format!("{:x}", hash_result)
+        crate::signer(cashbox_password)
+            .sign(self)
+            .expect("Request is serialized into an object")
     }
 }
 
 impl Tokenizable for TokenInfoRequest {
     fn validate_token(&self, password: &Secret<String>) -> Result<(), ()> {
-        let token = self.generate_token(password);
-        if token.eq(&self.token) {
+        if crate::signer(password).verify(self, &self.token) {
             Ok(())
         } else {
             Err(())
@@ -0,0 +1,113 @@
+use airactions::testing::{Mock, MockServer};
+use airactions::transport::HttpResponse;
+use banksim_api::init_payment::{
+    InitPayment, InitPaymentRequest, InitPaymentResponse,
+};
+use banksim_api::session::webhook::{Webhook, WebhookRequest, WebhookResponse};
+use banksim_api::token_info::{TokenInfo, TokenInfoRequest, TokenInfoResponse};
+use banksim_api::{Client, ClientError, Method, StatusCode};
+use banksim_api::{Operation, OperationStatus, Tokenizable};
+use secrecy::Secret;
+use url::Url;
+use uuid::Uuid;
+
+fn password() -> Secret<String> {
+    Secret::new("password".to_string())
+}
+
+fn url(path: &str) -> Url {
+    Url::parse("https://shop.happydog.org")
+        .unwrap()
+        .join(path)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn init_payment_sends_signed_request() {
+    let server = MockServer::start().await;
+    let session_id = Uuid::new_v4();
+    let response =
+        InitPaymentResponse::operation_success(url("/session"), session_id);
+    server.mock(
+        Mock::given(Method::POST, "/session/init/payment")
+            .with_json_body(serde_json::json!({ "amount": 100 }))
+            .matching(|req| {
+                serde_json::from_slice::<InitPaymentRequest>(&req.body)
+                    .is_ok_and(|req| req.validate_token(&password()).is_ok())
+            })
+            .respond_with_json(StatusCode::OK, &response)
+            .expect(1),
+    );
+    let client = Client::new(server.url()).unwrap();
+    let request = InitPaymentRequest::new(
+        url("/notify"),
+        url("/success"),
+        url("/fail"),
+        100,
+        &password(),
+        None,
+    );
+    let response = client.execute(InitPayment, request).await.unwrap();
+    assert_eq!(response.payment_id, Some(session_id));
+    assert!(matches!(response.status, OperationStatus::Success));
+}
+
+#[tokio::test]
+async fn token_info_reports_inactive_token() {
+    let server = MockServer::start().await;
+    server.mock(
+        Mock::given(Method::POST, "/token/info")
+            .with_json_body(serde_json::json!({ "card_token": "abc" }))
+            .respond_with_json(
+                StatusCode::OK,
+                &TokenInfoResponse { status: Ok(false) },
+            )
+            .expect(1),
+    );
+    let client = Client::new(server.url()).unwrap();
+    let request = TokenInfoRequest::new("abc".to_string(), &password());
+    let response = client.execute(TokenInfo, request).await.unwrap();
+    assert_eq!(response.status, Ok(false));
+}
+
+#[tokio::test]
+async fn webhook_variants_use_own_paths() {
+    let server = MockServer::start().await;
+    let session_id = Uuid::new_v4();
+    for path in ["/session/confirm", "/session/cancel"] {
+        server.mock(
+            Mock::given(Method::POST, path)
+                .respond_with_json(
+                    StatusCode::OK,
+                    &WebhookResponse {
+                        session_id,
+                        status: OperationStatus::Success,
+                    },
+                )
+                .expect(1),
+        );
+    }
+    let client = Client::new(server.url()).unwrap();
+    for webhook in [Webhook::Confirm, Webhook::Cancel] {
+        let request = WebhookRequest::new(session_id, &password());
+        let response = client.execute(webhook, request).await.unwrap();
+        assert_eq!(response.session_id, session_id);
+    }
+}
+
+#[tokio::test]
+async fn server_error_is_reported_as_status_error() {
+    let server = MockServer::start().await;
+    server.mock(
+        Mock::given(Method::POST, "/token/info")
+            .respond_with(HttpResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal error",
+            ))
+            .expect(1),
+    );
+    let client = Client::new(server.url()).unwrap();
+    let request = TokenInfoRequest::new("abc".to_string(), &password());
+    let response = client.execute(TokenInfo, request).await;
+    assert!(matches!(response, Err(ClientError::StatusError(_))));
+}
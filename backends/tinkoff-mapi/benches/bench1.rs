@@ -2,6 +2,7 @@
 
 use criterion::{criterion_group, criterion_main, Criterion};
 use rust_decimal::Decimal;
+use secrecy::Secret;
 use tinkoff_mapi::domain::{Email, Kopeck};
 use tinkoff_mapi::payment::{OrderId, Payment, TerminalType};
 use tinkoff_mapi::payment_data::{OperationInitiatorType, PaymentData};
@@ -53,7 +54,7 @@ fn benchmark_payment_json_creation(c: &mut Criterion) {
             )
             .with_payment_data(payment_data)
             .with_receipt(receipt)
-            .build()
+            .build(&Secret::new("secret".to_string()))
             .unwrap();
         });
     });
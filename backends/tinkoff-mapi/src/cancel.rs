@@ -0,0 +1,205 @@
+use airactions::signing::SignError;
+use airactions::transport::HttpResponse;
+use airactions::{ApiAction, ClientError, HttpClient, Url};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Kopeck, PaymentStatus};
+use crate::payment::Shop;
+use crate::receipt::Receipt;
+
+// ───── Api Action ───────────────────────────────────────────────────────── //
+
+/// Отмена платежа: снятие блокировки средств или возврат (полный
+/// или частичный) уже списанных.
+pub struct CancelAction;
+
+impl ApiAction for CancelAction {
+    type Request = CancelRequest;
+    type Response = CancelResponse;
+    fn url_path(&self) -> &'static str {
+        "Cancel"
+    }
+    /// Повторные возвраты платежа с тем же `ExternalRequestId`
+    /// не выполняются.
+    fn idempotency_key(&self, req: &Self::Request) -> Option<String> {
+        let id = req.external_request_id.as_ref()?;
+        Some(format!("{}:{}:{}", req.terminal_key, req.payment_id, id))
+    }
+    fn is_final_response(response: &HttpResponse) -> bool {
+        crate::is_success(response)
+    }
+    async fn perform_action(
+        req: Self::Request,
+        addr: Url,
+        client: &HttpClient<'_>,
+    ) -> Result<Self::Response, ClientError> {
+        client
+            .post(addr)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+    }
+}
+
+// ───── Request Type ─────────────────────────────────────────────────────── //
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CancelRequest {
+    /// Идентификатор терминала.
+    terminal_key: String,
+    /// Идентификатор платежа в системе Тинькофф Кассы.
+    payment_id: u64,
+    /// Сумма возврата в копейках. Если не передана, возвращается
+    /// вся сумма платежа.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<Kopeck>,
+    /// Чек возврата.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<Receipt>,
+    /// Данные маркетплейса, суммы возврата по магазинам.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shops: Option<Vec<Shop>>,
+    /// Идентификатор операции на стороне Мерчанта.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_request_id: Option<String>,
+    /// Подпись запроса.
+    token: String,
+}
+
+impl CancelRequest {
+    pub fn builder(terminal_key: &str, payment_id: u64) -> CancelBuilder {
+        CancelBuilder {
+            terminal_key: terminal_key.to_string(),
+            payment_id,
+            amount: None,
+            receipt: None,
+            shops: None,
+            external_request_id: None,
+        }
+    }
+}
+
+pub struct CancelBuilder {
+    terminal_key: String,
+    payment_id: u64,
+    amount: Option<Kopeck>,
+    receipt: Option<Receipt>,
+    shops: Option<Vec<Shop>>,
+    external_request_id: Option<String>,
+}
+
+impl CancelBuilder {
+    /// Сумма частичного возврата.
+    pub fn with_amount(mut self, amount: Kopeck) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+    /// Чек возврата, собирается тем же `Receipt::builder`, что и чек
+    /// оплаты.
+    pub fn with_receipt(mut self, receipt: Receipt) -> Self {
+        self.receipt = Some(receipt);
+        self
+    }
+    pub fn with_shops(mut self, shops: Vec<Shop>) -> Self {
+        self.shops = Some(shops);
+        self
+    }
+    /// Идентификатор операции на стороне Мерчанта, защищает от
+    /// повторного возврата.
+    pub fn with_external_request_id(mut self, id: String) -> Self {
+        self.external_request_id = Some(id);
+        self
+    }
+    /// Подписать запрос паролем терминала.
+    pub fn build(
+        self,
+        password: &Secret<String>,
+    ) -> Result<CancelRequest, SignError> {
+        let mut req = CancelRequest {
+            terminal_key: self.terminal_key,
+            payment_id: self.payment_id,
+            amount: self.amount,
+            receipt: self.receipt,
+            shops: self.shops,
+            external_request_id: self.external_request_id,
+            token: String::new(),
+        };
+        req.token = crate::sign(&req, password)?;
+        Ok(req)
+    }
+}
+
+// ───── Response Type ────────────────────────────────────────────────────── //
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct CancelResponse {
+    /// Идентификатор терминала.
+    pub terminal_key: Option<String>,
+    /// Идентификатор заказа в системе Мерчанта
+    pub order_id: Option<String>,
+    /// Успешность запроса
+    pub success: bool,
+    /// Статус платежа после отмены
+    pub status: Option<PaymentStatus>,
+    /// Идентификатор платежа в системе Тинькофф Кассы
+    pub payment_id: Option<u64>,
+    /// Код ошибки. «0» в случае успеха
+    pub error_code: String,
+    /// Сумма в копейках до операции отмены
+    pub original_amount: Option<u64>,
+    /// Сумма в копейках после операции отмены
+    pub new_amount: Option<u64>,
+    /// Краткое описание ошибки
+    pub message: Option<String>,
+    /// Подробное описание ошибки
+    pub details: Option<String>,
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use secrecy::Secret;
+    use sha2::{Digest, Sha256};
+
+    use super::{CancelRequest, CancelResponse};
+    use crate::domain::{Kopeck, PaymentStatus};
+
+    #[test]
+    fn partial_refund_signs_amount_and_external_id() {
+        let password = Secret::new("usaf8fw8fsw21g".to_string());
+        let req = CancelRequest::builder("TinkoffBankTest", 13660)
+            .with_amount(Kopeck::from_rub(Decimal::new(500, 2)).unwrap())
+            .with_external_request_id("refund-1".to_string())
+            .build(&password)
+            .unwrap();
+        // Amount, ExternalRequestId, Password, PaymentId, TerminalKey
+        let expected =
+            Sha256::digest("500refund-1usaf8fw8fsw21g13660TinkoffBankTest");
+        assert_eq!(req.token, format!("{:x}", expected));
+    }
+
+    #[test]
+    fn response_exposes_amounts() {
+        let body = serde_json::json!({
+            "Success": true,
+            "ErrorCode": "0",
+            "TerminalKey": "TinkoffBankTest",
+            "Status": "PARTIAL_REFUNDED",
+            "PaymentId": 13660,
+            "OrderId": "21050",
+            "OriginalAmount": 1000,
+            "NewAmount": 500,
+        });
+        let response: CancelResponse = serde_json::from_value(body).unwrap();
+        assert_eq!(response.status, Some(PaymentStatus::PartialRefunded));
+        assert_eq!(response.original_amount, Some(1000));
+        assert_eq!(response.new_amount, Some(500));
+    }
+}
@@ -0,0 +1,217 @@
+use airactions::signing::SignError;
+use airactions::transport::HttpResponse;
+use airactions::{ApiAction, ClientError, HttpClient, Url};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Email, PaymentStatus};
+use crate::error_chain_fmt;
+use crate::payment_data::OperationInitiatorType;
+
+// ───── Api Action ───────────────────────────────────────────────────────── //
+
+/// Автоплатеж: списание по сохраненным реквизитам (`RebillId`)
+/// в рамках платежа, созданного методом Init.
+pub struct ChargeAction;
+
+impl ApiAction for ChargeAction {
+    type Request = ChargeRequest;
+    type Response = ChargeResponse;
+    fn url_path(&self) -> &'static str {
+        "Charge"
+    }
+    /// Каждый платеж, созданный методом Init, списывается один раз.
+    fn idempotency_key(&self, req: &Self::Request) -> Option<String> {
+        Some(format!("{}:{}", req.terminal_key, req.payment_id))
+    }
+    fn is_final_response(response: &HttpResponse) -> bool {
+        crate::is_success(response)
+    }
+    async fn perform_action(
+        req: Self::Request,
+        addr: Url,
+        client: &HttpClient<'_>,
+    ) -> Result<Self::Response, ClientError> {
+        client
+            .post(addr)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+    }
+}
+
+// ───── Request Type ─────────────────────────────────────────────────────── //
+
+#[derive(thiserror::Error)]
+pub enum ChargeParseError {
+    #[error("Given OperationInitiatorType: {0:?} is not compatible with RebillId at Charge method")]
+    NotAllowedWithChargeError(OperationInitiatorType),
+    #[error("Failed to sign request")]
+    SignError(#[from] SignError),
+}
+
+impl std::fmt::Debug for ChargeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ChargeRequest {
+    /// Идентификатор терминала.
+    terminal_key: String,
+    /// Идентификатор платежа, полученный в ответе на Init.
+    payment_id: u64,
+    /// Идентификатор автоплатежа из нотификации родительского платежа.
+    rebill_id: u64,
+    /// Отправлять ли покупателю письмо об успешном списании.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    send_email: Option<bool>,
+    /// Адрес для отправки письма.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    info_email: Option<Email>,
+    /// Подпись запроса.
+    token: String,
+}
+
+impl ChargeRequest {
+    /// `initiator_type` — признак инициатора операции, переданный
+    /// в `PaymentData` при Init родительского платежа.
+    pub fn builder(
+        terminal_key: &str,
+        payment_id: u64,
+        rebill_id: u64,
+        initiator_type: OperationInitiatorType,
+    ) -> ChargeBuilder {
+        ChargeBuilder {
+            terminal_key: terminal_key.to_string(),
+            payment_id,
+            rebill_id,
+            initiator_type,
+            send_email: None,
+            info_email: None,
+        }
+    }
+}
+
+pub struct ChargeBuilder {
+    terminal_key: String,
+    payment_id: u64,
+    rebill_id: u64,
+    initiator_type: OperationInitiatorType,
+    send_email: Option<bool>,
+    info_email: Option<Email>,
+}
+
+impl ChargeBuilder {
+    /// Получение покупателем уведомлений на электронную почту.
+    pub fn with_send_email(mut self, send_email: bool) -> Self {
+        self.send_email = Some(send_email);
+        self
+    }
+    /// Электронная почта покупателя для уведомлений.
+    pub fn with_info_email(mut self, email: Email) -> Self {
+        self.info_email = Some(email);
+        self
+    }
+    /// Проверить признак инициатора и подписать запрос паролем терминала.
+    /// Списание по `RebillId` допускается только для рекуррентных
+    /// операций (`CIT_COF`, `CIT_COF_R`, `CIT_COF_I`).
+    pub fn build(
+        self,
+        password: &Secret<String>,
+    ) -> Result<ChargeRequest, ChargeParseError> {
+        let init_type = self.initiator_type;
+        if init_type.allowed_with_rebill_id_at_charge().is_none() {
+            return Err(ChargeParseError::NotAllowedWithChargeError(init_type));
+        }
+        let mut req = ChargeRequest {
+            terminal_key: self.terminal_key,
+            payment_id: self.payment_id,
+            rebill_id: self.rebill_id,
+            send_email: self.send_email,
+            info_email: self.info_email,
+            token: String::new(),
+        };
+        req.token = crate::sign(&req, password)?;
+        Ok(req)
+    }
+}
+
+// ───── Response Type ────────────────────────────────────────────────────── //
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct ChargeResponse {
+    /// Идентификатор терминала.
+    pub terminal_key: Option<String>,
+    /// Сумма в копейках
+    pub amount: Option<u64>,
+    /// Идентификатор заказа в системе Мерчанта
+    pub order_id: Option<String>,
+    /// Успешность запроса
+    pub success: bool,
+    /// Статус платежа, `CONFIRMED` в случае успеха
+    pub status: Option<PaymentStatus>,
+    /// Идентификатор платежа в системе Тинькофф Кассы
+    pub payment_id: Option<u64>,
+    /// Код ошибки. «0» в случае успеха
+    pub error_code: String,
+    /// Краткое описание ошибки
+    pub message: Option<String>,
+    /// Подробное описание ошибки
+    pub details: Option<String>,
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+    use sha2::{Digest, Sha256};
+
+    use super::{ChargeParseError, ChargeRequest};
+    use crate::domain::Email;
+    use crate::payment_data::OperationInitiatorType;
+
+    fn password() -> Secret<String> {
+        Secret::new("usaf8fw8fsw21g".to_string())
+    }
+
+    #[test]
+    fn charge_with_email_is_signed() {
+        let req = ChargeRequest::builder(
+            "TinkoffBankTest",
+            13660,
+            145919,
+            OperationInitiatorType::CIT_COF_R,
+        )
+        .with_send_email(true)
+        .with_info_email(Email::parse("user@example.com").unwrap())
+        .build(&password())
+        .unwrap();
+        // InfoEmail, Password, PaymentId, RebillId, SendEmail, TerminalKey
+        let expected = Sha256::digest(
+            "user@example.comusaf8fw8fsw21g13660145919trueTinkoffBankTest",
+        );
+        assert_eq!(req.token, format!("{:x}", expected));
+    }
+
+    #[test]
+    fn customer_initiated_payment_is_not_charged() {
+        let req = ChargeRequest::builder(
+            "TinkoffBankTest",
+            13660,
+            145919,
+            OperationInitiatorType::CIT_CC,
+        )
+        .build(&password());
+        assert!(matches!(
+            req,
+            Err(ChargeParseError::NotAllowedWithChargeError(_))
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+//! Профили окружений Тинькофф Кассы.
+//!
+//! ```rust,no_run
+//! use tinkoff_mapi::config::TinkoffConfig;
+//!
+//! // TINKOFF_ENVIRONMENT=test TINKOFF_KEY=... TINKOFF_PASSWORD=...
+//! let config = TinkoffConfig::from_env().unwrap();
+//! ```
+
+use airactions::config::{ConfigError, Preset, Profile};
+use airactions::Client;
+use secrecy::Secret;
+
+/// Боевое окружение.
+pub const PRODUCTION: Preset =
+    Preset::new("production", "https://securepay.tinkoff.ru/v2");
+/// Тестовое окружение.
+pub const TEST: Preset =
+    Preset::new("test", "https://rest-api-test.tinkoff.ru/v2");
+pub const PRESETS: &[Preset] = &[PRODUCTION, TEST];
+
+/// Префикс переменных окружения.
+pub const ENV_PREFIX: &str = "TINKOFF";
+
+/// Клиент и учетные данные терминала.
+#[derive(Debug)]
+pub struct TinkoffConfig {
+    pub client: Client,
+    /// Идентификатор терминала.
+    pub terminal_key: String,
+    /// Пароль терминала, используется при генерации токена.
+    pub password: Secret<String>,
+}
+
+impl TinkoffConfig {
+    /// `key` профиля используется как `TerminalKey`.
+    pub fn from_profile(profile: &Profile) -> Result<Self, ConfigError> {
+        Ok(TinkoffConfig {
+            client: profile.client(PRESETS)?,
+            terminal_key: profile.key.clone().ok_or(ConfigError::MissingKey)?,
+            password: profile.password.clone(),
+        })
+    }
+
+    /// Загрузить профиль из переменных `TINKOFF_*`.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        TinkoffConfig::from_profile(&Profile::from_env(ENV_PREFIX)?)
+    }
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use airactions::config::{ConfigError, Profile};
+
+    use super::{TinkoffConfig, TEST};
+
+    fn profile(value: serde_json::Value) -> Profile {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn terminal_key_is_required() {
+        let profile = profile(serde_json::json!({
+            "environment": "test",
+            "password": "secret",
+        }));
+        assert!(matches!(
+            TinkoffConfig::from_profile(&profile),
+            Err(ConfigError::MissingKey)
+        ));
+    }
+
+    #[test]
+    fn test_preset_is_used_for_sandbox() {
+        let profile = profile(serde_json::json!({
+            "environment": "test",
+            "key": "TinkoffBankTest",
+            "password": "secret",
+        }));
+        let config = TinkoffConfig::from_profile(&profile).unwrap();
+        assert_eq!(config.terminal_key, "TinkoffBankTest");
+        assert_eq!(profile.base_url(&[TEST]).unwrap().as_str(), TEST.base_url);
+    }
+}
@@ -0,0 +1,214 @@
+use airactions::signing::SignError;
+use airactions::transport::HttpResponse;
+use airactions::{ApiAction, ClientError, HttpClient, Url};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Kopeck, PaymentStatus};
+use crate::payment::Shop;
+use crate::receipt::Receipt;
+
+// ───── Api Action ───────────────────────────────────────────────────────── //
+
+/// Подтверждение двухстадийного платежа (`PayType::T`): списание
+/// заблокированных средств.
+pub struct ConfirmAction;
+
+impl ApiAction for ConfirmAction {
+    type Request = ConfirmRequest;
+    type Response = ConfirmResponse;
+    fn url_path(&self) -> &'static str {
+        "Confirm"
+    }
+    /// Платеж подтверждается один раз, повтор с другой суммой
+    /// отправляется как новая операция.
+    fn idempotency_key(&self, req: &Self::Request) -> Option<String> {
+        let amount = match req.amount {
+            Some(ref amount) => amount.to_string(),
+            None => "full".to_string(),
+        };
+        Some(format!(
+            "{}:{}:{}",
+            req.terminal_key, req.payment_id, amount
+        ))
+    }
+    fn is_final_response(response: &HttpResponse) -> bool {
+        crate::is_success(response)
+    }
+    async fn perform_action(
+        req: Self::Request,
+        addr: Url,
+        client: &HttpClient<'_>,
+    ) -> Result<Self::Response, ClientError> {
+        client
+            .post(addr)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+    }
+}
+
+// ───── Request Type ─────────────────────────────────────────────────────── //
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConfirmRequest {
+    /// Идентификатор терминала.
+    terminal_key: String,
+    /// Идентификатор платежа в системе Тинькофф Кассы.
+    payment_id: u64,
+    /// Сумма в копейках. Если не передана, списывается вся
+    /// заблокированная сумма.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<Kopeck>,
+    /// Чек на сумму подтверждения.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receipt: Option<Receipt>,
+    /// Данные маркетплейса.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shops: Option<Vec<Shop>>,
+    /// Подпись запроса.
+    token: String,
+}
+
+impl ConfirmRequest {
+    pub fn builder(terminal_key: &str, payment_id: u64) -> ConfirmBuilder {
+        ConfirmBuilder {
+            terminal_key: terminal_key.to_string(),
+            payment_id,
+            amount: None,
+            receipt: None,
+            shops: None,
+        }
+    }
+}
+
+pub struct ConfirmBuilder {
+    terminal_key: String,
+    payment_id: u64,
+    amount: Option<Kopeck>,
+    receipt: Option<Receipt>,
+    shops: Option<Vec<Shop>>,
+}
+
+impl ConfirmBuilder {
+    /// Сумма частичного подтверждения, не больше заблокированной.
+    pub fn with_amount(mut self, amount: Kopeck) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+    pub fn with_receipt(mut self, receipt: Receipt) -> Self {
+        self.receipt = Some(receipt);
+        self
+    }
+    pub fn with_shops(mut self, shops: Vec<Shop>) -> Self {
+        self.shops = Some(shops);
+        self
+    }
+    /// Подписать запрос паролем терминала.
+    pub fn build(
+        self,
+        password: &Secret<String>,
+    ) -> Result<ConfirmRequest, SignError> {
+        let mut req = ConfirmRequest {
+            terminal_key: self.terminal_key,
+            payment_id: self.payment_id,
+            amount: self.amount,
+            receipt: self.receipt,
+            shops: self.shops,
+            token: String::new(),
+        };
+        req.token = crate::sign(&req, password)?;
+        Ok(req)
+    }
+}
+
+// ───── Response Type ────────────────────────────────────────────────────── //
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConfirmResponse {
+    /// Идентификатор терминала.
+    pub terminal_key: Option<String>,
+    /// Идентификатор заказа в системе Мерчанта
+    pub order_id: Option<String>,
+    /// Успешность запроса
+    pub success: bool,
+    /// Статус платежа, `CONFIRMED` в случае успеха
+    pub status: Option<PaymentStatus>,
+    /// Идентификатор платежа в системе Тинькофф Кассы
+    pub payment_id: Option<u64>,
+    /// Код ошибки. «0» в случае успеха
+    pub error_code: String,
+    /// Краткое описание ошибки
+    pub message: Option<String>,
+    /// Подробное описание ошибки
+    pub details: Option<String>,
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use airactions::transport::HttpResponse;
+    use airactions::{ApiAction, StatusCode};
+    use rust_decimal::Decimal;
+    use secrecy::Secret;
+    use sha2::{Digest, Sha256};
+
+    use super::{ConfirmAction, ConfirmRequest};
+    use crate::domain::Kopeck;
+    use crate::payment::Shop;
+
+    #[test]
+    fn partial_confirm_signs_amount_but_not_shops() {
+        let password = Secret::new("usaf8fw8fsw21g".to_string());
+        let amount = || Kopeck::from_rub(Decimal::new(1000, 2)).unwrap();
+        let shop = Shop::new("123", amount(), None, None).unwrap();
+        let req = ConfirmRequest::builder("TinkoffBankTest", 13660)
+            .with_amount(amount())
+            .with_shops(vec![shop])
+            .build(&password)
+            .unwrap();
+        // Amount, Password, PaymentId, TerminalKey
+        let expected = Sha256::digest("1000usaf8fw8fsw21g13660TinkoffBankTest");
+        assert_eq!(req.token, format!("{:x}", expected));
+
+        let body = serde_json::to_value(&req).unwrap();
+        assert_eq!(body["Shops"][0]["ShopCode"], "123");
+        assert!(body.get("Receipt").is_none());
+    }
+
+    #[test]
+    fn corrected_amount_is_new_operation() {
+        let password = Secret::new("usaf8fw8fsw21g".to_string());
+        let confirm = |rub| {
+            ConfirmRequest::builder("TinkoffBankTest", 13660)
+                .with_amount(Kopeck::from_rub(Decimal::new(rub, 2)).unwrap())
+                .build(&password)
+                .unwrap()
+        };
+        let full = ConfirmRequest::builder("TinkoffBankTest", 13660)
+            .build(&password)
+            .unwrap();
+        let key = |req| ConfirmAction.idempotency_key(&req).unwrap();
+        assert_eq!(key(confirm(1000)), "TinkoffBankTest:13660:1000");
+        assert_ne!(key(confirm(1000)), key(confirm(900)));
+        assert_eq!(key(full), "TinkoffBankTest:13660:full");
+    }
+
+    #[test]
+    fn failed_confirm_is_not_final() {
+        let response = |success: bool| {
+            let body = serde_json::json!({
+                "Success": success,
+                "ErrorCode": if success { "0" } else { "9999" },
+            });
+            HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+        };
+        assert!(ConfirmAction::is_final_response(&response(true)));
+        assert!(!ConfirmAction::is_final_response(&response(false)));
+    }
+}
@@ -0,0 +1,290 @@
+use airactions::signing::SignError;
+use airactions::{ApiAction, ClientError, HttpClient, Url};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Email, PhoneNumber};
+use crate::error_chain_fmt;
+
+// ───── Api Actions ──────────────────────────────────────────────────────── //
+
+/// Регистрация покупателя, его `CustomerKey` затем передается в Init.
+pub struct AddCustomerAction;
+
+impl ApiAction for AddCustomerAction {
+    type Request = AddCustomerRequest;
+    type Response = CustomerResponse;
+    fn url_path(&self) -> &'static str {
+        "AddCustomer"
+    }
+    async fn perform_action(
+        req: Self::Request,
+        addr: Url,
+        client: &HttpClient<'_>,
+    ) -> Result<Self::Response, ClientError> {
+        client
+            .post(addr)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+    }
+}
+
+/// Получение данных покупателя.
+pub struct GetCustomerAction;
+
+impl ApiAction for GetCustomerAction {
+    type Request = CustomerRequest;
+    type Response = GetCustomerResponse;
+    const IDEMPOTENT: bool = true;
+    fn url_path(&self) -> &'static str {
+        "GetCustomer"
+    }
+    async fn perform_action(
+        req: Self::Request,
+        addr: Url,
+        client: &HttpClient<'_>,
+    ) -> Result<Self::Response, ClientError> {
+        client
+            .post(addr)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+    }
+}
+
+/// Удаление покупателя.
+pub struct RemoveCustomerAction;
+
+impl ApiAction for RemoveCustomerAction {
+    type Request = CustomerRequest;
+    type Response = CustomerResponse;
+    fn url_path(&self) -> &'static str {
+        "RemoveCustomer"
+    }
+    async fn perform_action(
+        req: Self::Request,
+        addr: Url,
+        client: &HttpClient<'_>,
+    ) -> Result<Self::Response, ClientError> {
+        client
+            .post(addr)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+    }
+}
+
+// ───── Request Types ────────────────────────────────────────────────────── //
+
+#[derive(thiserror::Error)]
+pub enum CustomerParseError {
+    #[error("Customer key is {0}, but max is 36")]
+    KeyTooLongError(usize),
+    #[error("Customer key is empty")]
+    EmptyKeyError,
+    #[error("Failed to sign request")]
+    SignError(#[from] SignError),
+}
+
+impl std::fmt::Debug for CustomerParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+fn parse_customer_key(key: &str) -> Result<String, CustomerParseError> {
+    if key.is_empty() {
+        return Err(CustomerParseError::EmptyKeyError);
+    }
+    let len = key.chars().count();
+    if len > 36 {
+        return Err(CustomerParseError::KeyTooLongError(len));
+    }
+    Ok(key.to_string())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AddCustomerRequest {
+    /// Идентификатор терминала.
+    terminal_key: String,
+    /// Идентификатор покупателя в системе Мерчанта.
+    customer_key: String,
+    /// Электронная почта покупателя.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<Email>,
+    /// Телефон покупателя в формате E.164.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phone: Option<PhoneNumber>,
+    /// Подпись запроса.
+    token: String,
+}
+
+impl AddCustomerRequest {
+    pub fn builder(
+        terminal_key: &str,
+        customer_key: &str,
+    ) -> AddCustomerBuilder {
+        AddCustomerBuilder {
+            terminal_key: terminal_key.to_string(),
+            customer_key: customer_key.to_string(),
+            email: None,
+            phone: None,
+        }
+    }
+}
+
+pub struct AddCustomerBuilder {
+    terminal_key: String,
+    customer_key: String,
+    email: Option<Email>,
+    phone: Option<PhoneNumber>,
+}
+
+impl AddCustomerBuilder {
+    pub fn with_email(mut self, email: Email) -> Self {
+        self.email = Some(email);
+        self
+    }
+    pub fn with_phone(mut self, phone: PhoneNumber) -> Self {
+        self.phone = Some(phone);
+        self
+    }
+    /// Проверить `CustomerKey` и подписать запрос паролем терминала.
+    pub fn build(
+        self,
+        password: &Secret<String>,
+    ) -> Result<AddCustomerRequest, CustomerParseError> {
+        let mut req = AddCustomerRequest {
+            terminal_key: self.terminal_key,
+            customer_key: parse_customer_key(&self.customer_key)?,
+            email: self.email,
+            phone: self.phone,
+            token: String::new(),
+        };
+        req.token = crate::sign(&req, password)?;
+        Ok(req)
+    }
+}
+
+/// Запрос методов GetCustomer и RemoveCustomer.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomerRequest {
+    /// Идентификатор терминала.
+    terminal_key: String,
+    /// Идентификатор покупателя в системе Мерчанта.
+    customer_key: String,
+    /// Подпись запроса.
+    token: String,
+}
+
+impl CustomerRequest {
+    pub fn new(
+        terminal_key: &str,
+        customer_key: &str,
+        password: &Secret<String>,
+    ) -> Result<Self, CustomerParseError> {
+        let mut req = CustomerRequest {
+            terminal_key: terminal_key.to_string(),
+            customer_key: parse_customer_key(customer_key)?,
+            token: String::new(),
+        };
+        req.token = crate::sign(&req, password)?;
+        Ok(req)
+    }
+}
+
+// ───── Response Types ───────────────────────────────────────────────────── //
+
+/// Ответ методов AddCustomer и RemoveCustomer.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomerResponse {
+    /// Идентификатор терминала.
+    pub terminal_key: Option<String>,
+    /// Идентификатор покупателя в системе Мерчанта
+    pub customer_key: Option<String>,
+    /// Успешность запроса
+    pub success: bool,
+    /// Код ошибки. «0» в случае успеха
+    pub error_code: String,
+    /// Краткое описание ошибки
+    pub message: Option<String>,
+    /// Подробное описание ошибки
+    pub details: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetCustomerResponse {
+    /// Идентификатор терминала.
+    pub terminal_key: Option<String>,
+    /// Идентификатор покупателя в системе Мерчанта
+    pub customer_key: Option<String>,
+    /// Успешность запроса
+    pub success: bool,
+    /// Код ошибки. «0» в случае успеха
+    pub error_code: String,
+    /// Электронная почта покупателя
+    pub email: Option<String>,
+    /// Телефон покупателя
+    pub phone: Option<String>,
+    /// Краткое описание ошибки
+    pub message: Option<String>,
+    /// Подробное описание ошибки
+    pub details: Option<String>,
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+    use sha2::{Digest, Sha256};
+
+    use super::{AddCustomerRequest, CustomerParseError, CustomerRequest};
+    use crate::domain::{Email, PhoneNumber};
+
+    fn password() -> Secret<String> {
+        Secret::new("usaf8fw8fsw21g".to_string())
+    }
+
+    #[test]
+    fn add_customer_signs_email_and_phone() {
+        let phone = PhoneNumber::parse("+79031234567").unwrap();
+        let req = AddCustomerRequest::builder("TinkoffBankTest", "user-1")
+            .with_email(Email::parse("user@example.com").unwrap())
+            .with_phone(phone)
+            .build(&password())
+            .unwrap();
+        // CustomerKey, Email, Password, Phone, TerminalKey
+        let expected = Sha256::digest(
+            "user-1user@example.comusaf8fw8fsw21g+79031234567TinkoffBankTest",
+        );
+        assert_eq!(req.token, format!("{:x}", expected));
+    }
+
+    #[test]
+    fn customer_key_is_validated() {
+        let key = "k".repeat(37);
+        let req = CustomerRequest::new("TinkoffBankTest", &key, &password());
+        assert!(matches!(req, Err(CustomerParseError::KeyTooLongError(37))));
+        let req = CustomerRequest::new("TinkoffBankTest", "", &password());
+        assert!(matches!(req, Err(CustomerParseError::EmptyKeyError)));
+    }
+
+    #[test]
+    fn customer_key_length_is_counted_in_chars() {
+        let key = "ключ".repeat(9);
+        let req = CustomerRequest::new("TinkoffBankTest", &key, &password());
+        assert!(req.is_ok());
+    }
+}
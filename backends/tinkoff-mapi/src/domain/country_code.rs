@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 pub struct CountryCode(String);
 
 impl CountryCode {
+    #[allow(clippy::result_unit_err)]
     pub fn new(code: &str) -> Result<CountryCode, ()> {
         if code.len() != 3 {
             Err(())
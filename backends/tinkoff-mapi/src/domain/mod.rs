@@ -1,7 +1,11 @@
 mod country_code;
 mod email;
 mod kopeck;
+mod payment_status;
+mod phone_number;
 
 pub use country_code::CountryCode;
 pub use email::Email;
 pub use kopeck::Kopeck;
+pub use payment_status::PaymentStatus;
+pub use phone_number::PhoneNumber;
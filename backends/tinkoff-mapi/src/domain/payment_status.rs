@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+/// Статус платежа в Тинькофф Кассе.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PaymentStatus {
+    /// Платеж создан
+    New,
+    /// Покупатель открыл платежную форму
+    FormShowed,
+    /// Платеж обрабатывается
+    Authorizing,
+    /// Покупатель проходит проверку 3-D Secure
+    #[serde(rename = "3DS_CHECKING")]
+    ThreeDsChecking,
+    /// Проверка 3-D Secure пройдена
+    #[serde(rename = "3DS_CHECKED")]
+    ThreeDsChecked,
+    /// Средства заблокированы, но не списаны (двухстадийная оплата)
+    Authorized,
+    /// Платеж подтверждается
+    Confirming,
+    /// Средства списаны
+    Confirmed,
+    /// Блокировка средств отменяется
+    Reversing,
+    /// Блокировка средств отменена частично
+    PartialReversed,
+    /// Блокировка средств отменена
+    Reversed,
+    /// Возврат обрабатывается
+    Refunding,
+    /// Средства возвращены частично
+    PartialRefunded,
+    /// Средства возвращены полностью
+    Refunded,
+    /// Банк отклонил платеж
+    Rejected,
+    /// Время оплаты истекло
+    DeadlineExpired,
+    /// Платеж завершился ошибкой или не прошел проверку 3-D Secure
+    AuthFail,
+    /// Мерчант отменил платеж
+    Canceled,
+    /// Статус, неизвестный этой версии библиотеки
+    #[serde(other)]
+    Unknown,
+}
+
+impl PaymentStatus {
+    /// Платеж больше не изменит статус без действий Мерчанта.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            PaymentStatus::Authorized
+                | PaymentStatus::Confirmed
+                | PaymentStatus::PartialReversed
+                | PaymentStatus::Reversed
+                | PaymentStatus::PartialRefunded
+                | PaymentStatus::Refunded
+                | PaymentStatus::Rejected
+                | PaymentStatus::DeadlineExpired
+                | PaymentStatus::AuthFail
+                | PaymentStatus::Canceled
+        )
+    }
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use super::PaymentStatus;
+
+    fn parse(status: &str) -> PaymentStatus {
+        serde_json::from_value(serde_json::json!(status)).unwrap()
+    }
+
+    #[test]
+    fn statuses_are_parsed_from_tinkoff_names() {
+        assert_eq!(parse("NEW"), PaymentStatus::New);
+        assert_eq!(parse("FORM_SHOWED"), PaymentStatus::FormShowed);
+        assert_eq!(parse("3DS_CHECKING"), PaymentStatus::ThreeDsChecking);
+        assert_eq!(parse("PARTIAL_REFUNDED"), PaymentStatus::PartialRefunded);
+        assert_eq!(parse("DEADLINE_EXPIRED"), PaymentStatus::DeadlineExpired);
+    }
+
+    #[test]
+    fn unknown_status_is_not_an_error() {
+        assert_eq!(parse("ASYNC_PROCESSING"), PaymentStatus::Unknown);
+        assert!(!PaymentStatus::Unknown.is_final());
+    }
+}
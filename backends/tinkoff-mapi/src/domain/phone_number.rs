@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error_chain_fmt;
+
+#[derive(thiserror::Error)]
+pub enum PhoneNumberError {
+    #[error("Not valid phone number")]
+    NotValidPhoneNumber,
+}
+
+impl std::fmt::Debug for PhoneNumberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// This type guarantees correctness of customer's phone number
+/// and stores it in E.164 format.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    pub fn parse(number: &str) -> Result<Self, PhoneNumberError> {
+        match phonenumber::parse(None, number) {
+            Ok(number) if phonenumber::is_valid(&number) => Ok(Self(
+                number.format().mode(phonenumber::Mode::E164).to_string(),
+            )),
+            _ => Err(PhoneNumberError::NotValidPhoneNumber),
+        }
+    }
+}
+
+impl AsRef<str> for PhoneNumber {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PhoneNumber;
+
+    #[test]
+    fn valid_number_is_formatted_as_e164() {
+        let number = PhoneNumber::parse("+7 (903) 123-45-67").unwrap();
+        assert_eq!(number.as_ref(), "+79031234567");
+    }
+
+    #[test]
+    fn number_without_country_code_is_rejected() {
+        assert!(PhoneNumber::parse("9031234567").is_err());
+    }
+
+    #[test]
+    fn invalid_number_is_rejected() {
+        assert!(PhoneNumber::parse("+7 000 000-00-00").is_err());
+    }
+}
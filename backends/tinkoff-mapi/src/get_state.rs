@@ -0,0 +1,123 @@
+use airactions::signing::SignError;
+use airactions::{ApiAction, ClientError, HttpClient, Url};
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::PaymentStatus;
+
+// ───── Api Action ───────────────────────────────────────────────────────── //
+
+/// Получение текущего статуса платежа.
+pub struct GetStateAction;
+
+impl ApiAction for GetStateAction {
+    type Request = GetStateRequest;
+    type Response = GetStateResponse;
+    const IDEMPOTENT: bool = true;
+    fn url_path(&self) -> &'static str {
+        "GetState"
+    }
+    async fn perform_action(
+        req: Self::Request,
+        addr: Url,
+        client: &HttpClient<'_>,
+    ) -> Result<Self::Response, ClientError> {
+        client
+            .post(addr)
+            .json(&req)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+    }
+}
+
+// ───── Request Type ─────────────────────────────────────────────────────── //
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetStateRequest {
+    /// Идентификатор терминала.
+    terminal_key: String,
+    /// Идентификатор платежа в системе Тинькофф Кассы.
+    payment_id: u64,
+    /// Подпись запроса.
+    token: String,
+}
+
+impl GetStateRequest {
+    pub fn new(
+        terminal_key: &str,
+        payment_id: u64,
+        password: &Secret<String>,
+    ) -> Result<Self, SignError> {
+        let mut req = GetStateRequest {
+            terminal_key: terminal_key.to_string(),
+            payment_id,
+            token: String::new(),
+        };
+        req.token = crate::sign(&req, password)?;
+        Ok(req)
+    }
+}
+
+// ───── Response Type ────────────────────────────────────────────────────── //
+
+/// В ответе с ошибкой могут быть только `Success`, `ErrorCode`,
+/// `Message` и `Details`, поэтому остальные поля необязательны.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct GetStateResponse {
+    /// Идентификатор терминала.
+    pub terminal_key: Option<String>,
+    /// Сумма в копейках
+    pub amount: Option<u64>,
+    /// Идентификатор заказа в системе Мерчанта
+    pub order_id: Option<String>,
+    /// Успешность запроса
+    pub success: bool,
+    /// Статус платежа
+    pub status: Option<PaymentStatus>,
+    /// Идентификатор платежа в системе Тинькофф Кассы
+    pub payment_id: Option<u64>,
+    /// Код ошибки. «0» в случае успеха
+    pub error_code: String,
+    /// Краткое описание ошибки
+    pub message: Option<String>,
+    /// Подробное описание ошибки
+    pub details: Option<String>,
+}
+
+// ───── Tests ────────────────────────────────────────────────────────────── //
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+    use sha2::{Digest, Sha256};
+
+    use super::{GetStateRequest, GetStateResponse};
+
+    #[test]
+    fn token_includes_password_sorted_by_key() {
+        let password = Secret::new("usaf8fw8fsw21g".to_string());
+        let req =
+            GetStateRequest::new("TinkoffBankTest", 13660, &password).unwrap();
+        // Password, PaymentId, TerminalKey
+        let expected = Sha256::digest("usaf8fw8fsw21g13660TinkoffBankTest");
+        assert_eq!(req.token, format!("{:x}", expected));
+    }
+
+    #[test]
+    fn error_response_keeps_error_code() {
+        let body = serde_json::json!({
+            "Success": false,
+            "ErrorCode": "204",
+            "Message": "Неверный токен",
+            "Details": "Проверьте пару TerminalKey/SecretKey",
+        });
+        let response: GetStateResponse = serde_json::from_value(body).unwrap();
+        assert!(!response.success);
+        assert_eq!(response.error_code, "204");
+        assert!(response.status.is_none());
+    }
+}
@@ -10,12 +10,24 @@ use time::format_description::well_known::iso8601::TimePrecision;
 use time::format_description::well_known::Iso8601;
 use url::Url;
 
-use airactions::ApiAction;
+use airactions::signing::{RequestSigner, SignError};
+use airactions::transport::HttpResponse;
+#[cfg(feature = "blocking")]
+pub use airactions::BlockingClient;
 pub use airactions::Client;
+use airactions::{ApiAction, HttpClient};
+use secrecy::Secret;
 
+use self::domain::PaymentStatus;
 use self::payment::Payment;
 
+pub mod cancel;
+pub mod charge;
+pub mod config;
+pub mod confirm;
+pub mod customer;
 pub mod domain;
+pub mod get_state;
 pub mod notifications;
 pub mod payment;
 pub mod payment_data;
@@ -45,7 +57,7 @@ pub struct InitPaymentResponse {
     /// Идентификатор терминала. Выдается Мерчанту Тинькофф Кассой при заведении терминала.
     terminal_key: String,
     /// Статус транзакции
-    status: String,
+    status: PaymentStatus,
     /// Идентификатор платежа в системе Тинькофф Кассы
     payment_id: u64,
     /// Идентификатор заказа в системе Мерчанта
@@ -66,19 +78,56 @@ impl ApiAction for InitPaymentAction {
     fn url_path(&self) -> &'static str {
         "Init"
     }
+    /// Повторный `Init` с тем же `OrderId` не создает новый платеж,
+    /// если клиент использует `airactions::idempotency::Idempotency`.
+    fn idempotency_key(&self, req: &Self::Request) -> Option<String> {
+        Some(format!("{}:{}", req.terminal_key(), req.order_id()))
+    }
+    fn is_final_response(response: &HttpResponse) -> bool {
+        is_success(response)
+    }
     async fn perform_action(
         req: Self::Request,
         addr: Url,
-        client: &reqwest::Client,
+        client: &HttpClient<'_>,
     ) -> Result<Self::Response, airactions::ClientError> {
-        let response =
-            client.post(addr).json(&req.inner()).send().await.unwrap();
-        Ok(response.json().await?)
+        client
+            .post(addr)
+            .json(&req.inner())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
     }
 }
 
 // ───── Functions ────────────────────────────────────────────────────────── //
 
+/// Подпись запроса: SHA-256 от значений полей верхнего уровня,
+/// отсортированных по ключу, вместе с паролем терминала (`Password`).
+pub(crate) fn sign(
+    req: &impl serde::Serialize,
+    password: &Secret<String>,
+) -> Result<String, SignError> {
+    RequestSigner::new()
+        .with_secret("Password", password)
+        .without_fields(&["Token"])
+        .sign(req)
+}
+
+/// Тинькофф Касса возвращает ошибки со статусом `200` и `Success: false`
+/// в теле, такие ответы не сохраняются для повторных запросов.
+pub(crate) fn is_success(response: &HttpResponse) -> bool {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Outcome {
+        success: bool,
+    }
+    response.status.is_success()
+        && serde_json::from_slice::<Outcome>(&response.body)
+            .is_ok_and(|outcome| outcome.success)
+}
+
 pub(crate) fn error_chain_fmt(
     e: &impl std::error::Error,
     f: &mut std::fmt::Formatter<'_>,
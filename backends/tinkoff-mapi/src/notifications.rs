@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{domain::Kopeck, receipt::Receipt};
+use crate::domain::{Kopeck, PaymentStatus};
+use crate::receipt::Receipt;
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -33,7 +34,7 @@ pub struct NotificationPayment {
     success: Option<bool>,
     /// Статус платежа
     #[serde(skip_serializing_if = "Option::is_none")]
-    status: Option<String>,
+    status: Option<PaymentStatus>,
     /// Уникальный идентификатор транзакции в системе Тинькофф Кассы
     #[serde(skip_serializing_if = "Option::is_none")]
     payment_id: Option<u64>,
@@ -66,6 +67,14 @@ pub struct NotificationPayment {
     data: Option<NotificationData>,
 }
 
+impl NotificationPayment {
+    /// Идентификатор автоплатежа для метода Charge, приходит после
+    /// оплаты с `Recurrent = Y`.
+    pub fn rebill_id(&self) -> Option<u64> {
+        self.rebill_id
+    }
+}
+
 /// Статус привязки карты. Получает в ответе 1 из 2 статусов привязки
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
@@ -245,7 +254,7 @@ pub enum Notification {
     /// Если нотификация за это время так и не доставлена, она складывается в дамп.
     NotificationAddCard(NotificationAddCard),
     /// Если используется подключенная онлайн касса, по результату фискализации будет отправлена нотификация с фискальными данными.
-    NotificationFiscalization(NotificationFiscalization),
+    NotificationFiscalization(Box<NotificationFiscalization>),
     /// После привязки счета по QR, магазину отправляется статус привязки и токен. Нотификация будет приходить по статусам ACTIVE и INACTIVE.
     NotificationQr(NotificationQr),
 }
@@ -1,8 +1,7 @@
-use std::collections::BTreeMap;
-
+use airactions::signing::SignError;
 use garde::Validate;
+use secrecy::Secret;
 use serde::{ser::Error, Serialize, Serializer};
-use sha2::{Digest, Sha256};
 use time::OffsetDateTime;
 use url::Url;
 
@@ -134,6 +133,8 @@ pub enum PaymentParseError {
     NotAllowedWithInitError(OperationInitiatorType),
     #[error("Given OperationInitiatorType: {0:?} is not compatible with given terminal type: {1:?}")]
     NotCompatibleTerminalError(OperationInitiatorType, TerminalType),
+    #[error("Failed to sign payment")]
+    SignError(#[from] SignError),
 }
 
 impl std::fmt::Debug for PaymentParseError {
@@ -172,6 +173,14 @@ impl Payment {
             terminal_type,
         }
     }
+    /// Идентификатор терминала.
+    pub fn terminal_key(&self) -> &str {
+        &self.0.terminal_key
+    }
+    /// Идентификатор заказа в системе Мерчанта.
+    pub fn order_id(&self) -> &OrderId {
+        &self.0.order_id
+    }
     pub(super) fn inner(&self) -> &PaymentBuilder {
         &self.0
     }
@@ -318,7 +327,11 @@ impl PaymentBuilder {
         self.descriptor = Some(desc);
         self
     }
-    pub fn build(mut self) -> Result<Payment, PaymentParseError> {
+    /// Проверить данные и подписать платеж паролем терминала.
+    pub fn build(
+        mut self,
+        password: &Secret<String>,
+    ) -> Result<Payment, PaymentParseError> {
         self.validate(&())?;
         if let Some(ref pd) = self.data {
             if let Some(init_type) = pd.initiator_type() {
@@ -340,52 +353,9 @@ impl PaymentBuilder {
                 }
             }
         }
-        let token = self.generate_token()?;
-        self.token = Some(token);
+        self.token = Some(crate::sign(&self, password)?);
         Ok(Payment(self))
     }
-
-    fn generate_token(&self) -> Result<String, PaymentParseError> {
-        // We need to get values concatenated, sorted by key, so
-        // using BTreeMap here.
-        let mut token_map = BTreeMap::new();
-        token_map.insert("TerminalKey", self.terminal_key.clone());
-        token_map.insert("Amount", self.amount.to_string());
-        token_map.insert("OrderId", self.order_id.to_string());
-        token_map.insert("Recurrent", self.recurrent.clone());
-        if let Some(ref desc) = self.description {
-            token_map.insert("Description", desc.clone());
-        }
-        if let Some(ref key) = self.customer_key {
-            token_map.insert("CustomerKey", key.clone());
-        }
-        if let Some(ref url) = self.notification_url {
-            token_map.insert("NotificationURL", url.clone().into());
-        }
-        if let Some(ref url) = self.success_url {
-            token_map.insert("SuccessURL", url.clone().into());
-        }
-        if let Some(ref url) = self.fail_url {
-            token_map.insert("FailURL", url.clone().into());
-        }
-        if let Some(ref date) = self.redirect_due_date {
-            let date = format_date_rfc3339(date)?;
-            token_map.insert("RedirectDueDate", date);
-        }
-        if let Some(ref desc) = self.descriptor {
-            token_map.insert("Descriptor", desc.clone());
-        }
-        let concatenated = token_map.into_values().collect::<String>();
-
-        // Hash the concatenated string with SHA-256
-        let mut hasher: Sha256 = Digest::new();
-        hasher.update(concatenated);
-        let hash_result = hasher.finalize();
-
-        // Convert hash result to a hex string
-        let token = format!("{:x}", hash_result);
-        Ok(token)
-    }
 }
 
 // ───── Functions ────────────────────────────────────────────────────────── //
@@ -450,6 +420,42 @@ mod tests {
         println!("{s}");
     }
 
+    #[test]
+    fn init_token_is_signed_with_password() {
+        let payment = Payment::builder(
+            "MerchantTerminalKey",
+            Kopeck::from_rub(Decimal::new(19200, 2)).unwrap(),
+            OrderId::I32(21090),
+            TerminalType::ECOM,
+        )
+        .with_description("Подарочная карта на 1000 рублей".to_string())
+        .build(&Secret::new("usaf8fw8fsw21g".to_string()))
+        .unwrap();
+        // Amount, Description, OrderId, Password, Recurrent, TerminalKey:
+        // "19200Подарочная карта на 1000 рублей21090usaf8fw8fsw21gNMerchantTerminalKey"
+        assert_eq!(
+            payment.0.token.as_deref(),
+            Some(
+                "9402c8e6a9f8133293e236424339b394f91a3f601e139065c36d85b09e978c47"
+            )
+        );
+    }
+
+    #[test]
+    fn unformattable_due_date_is_error() {
+        // RFC 3339 has no room for seconds in the UTC offset.
+        let offset = time::UtcOffset::from_hms(3, 0, 30).unwrap();
+        let payment = Payment::builder(
+            "MerchantTerminalKey",
+            Kopeck::from_rub(Decimal::new(19200, 2)).unwrap(),
+            OrderId::I32(21090),
+            TerminalType::ECOM,
+        )
+        .with_redirect_due_date(OffsetDateTime::now_utc().to_offset(offset))
+        .build(&Secret::new("usaf8fw8fsw21g".to_string()));
+        assert!(matches!(payment, Err(PaymentParseError::SignError(_))));
+    }
+
     #[test]
     fn test2() {
         use sha2::{Digest, Sha256};
@@ -244,12 +244,12 @@ impl SupplierInfo {
     /// - `phones`: Телефоны поставщика, в формате +{Ц}. Ограничения по длине: от 1 до 19 символов.
     ///
     /// - `name`: Наименование поставщика. Внимание: в данные 239 символов включаются
-    /// телефоны поставщика: 4 символа на каждый телефон.
-    /// Например, если передано два телефона поставщика длиной 12 и 14 символов,
-    /// то максимальная длина наименования поставщика будет 239 – (12 + 4) – (14 + 4) = 205 символов
+    ///   телефоны поставщика: 4 символа на каждый телефон.
+    ///   Например, если передано два телефона поставщика длиной 12 и 14 символов,
+    ///   то максимальная длина наименования поставщика будет 239 – (12 + 4) – (14 + 4) = 205 символов
     ///
     /// - `inn`: ИНН поставщика, в формате ЦЦЦЦЦЦЦЦЦЦ. Атрибут обязателен, если передается
-    /// значение AgentSign в объекте AgentData. Максимальная длина: 12 символов.
+    ///   значение AgentSign в объекте AgentData. Максимальная длина: 12 символов.
     pub fn new(
         phones: Option<Vec<PhoneNumber>>,
         name: Option<String>,
@@ -317,6 +317,7 @@ pub enum VatType {
 /// * `partial_payment` – частичный расчет и кредит
 /// * `credit` – передача в кредит
 /// * `credit_payment` – оплата кредита
+///
 /// Если значение не передано, по умолчанию в онлайн-кассу передается признак способа расчёта "full_payment".
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -543,9 +544,9 @@ impl Ffd12Data {
     /// Аргументы:
     /// * `payment_object` - `PaymentObjectFFD_12`, который категоризирует тип транзакции.
     /// * `payment_method` - `PaymentMethod`, который указывает метод, с помощью которого
-    /// происходит оплата.
+    ///   происходит оплата.
     /// * `measurement_unit` - `MeasurementUnit`, который определяет единицу измерения товаров
-    /// в транзакции.
+    ///   в транзакции.
     ///
     /// Возвращает:
     /// Экземпляр `Ffd12DataBuilder` с установленными обязательными полями и неустановленными необязательными полями,
@@ -781,8 +782,8 @@ impl Item {
     ///
     /// ```
     /// use rust_decimal::Decimal;
-    /// use mapi::domain::Kopeck;
-    /// use mapi::receipt::item::{VatType, Item, CashBoxType};
+    /// use tinkoff_mapi::domain::Kopeck;
+    /// use tinkoff_mapi::receipt::item::{VatType, Item, CashBoxType};
     ///
     /// let item_builder = Item::builder(
     ///     "Шоколадный батончик",
@@ -936,6 +937,7 @@ where
     S: Serializer,
 {
     let formatted_date = date
+        .assume_utc()
         .format(&time::format_description::well_known::Rfc3339)
         .map_err(Error::custom)?;
     serializer.serialize_str(&formatted_date)
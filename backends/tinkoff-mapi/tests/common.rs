@@ -1,5 +1,17 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use airactions::cassette::Cassette;
+use airactions::idempotency::Idempotency;
+use airactions::testing::{Mock, MockServer};
+use airactions::transport::{HttpResponse, InMemoryTransport};
+use airactions::{BlockingClient, Method, StatusCode};
 use rust_decimal::Decimal;
-use tinkoff_mapi::domain::{Email, Kopeck};
+use secrecy::Secret;
+use tinkoff_mapi::cancel::{CancelAction, CancelRequest};
+use tinkoff_mapi::domain::{Email, Kopeck, PaymentStatus};
+use tinkoff_mapi::get_state::{GetStateAction, GetStateRequest};
 use tinkoff_mapi::payment::{OrderId, Payment, TerminalType};
 use tinkoff_mapi::payment_data::{OperationInitiatorType, PaymentData};
 use tinkoff_mapi::receipt::item::{
@@ -8,8 +20,272 @@ use tinkoff_mapi::receipt::item::{
 use tinkoff_mapi::receipt::{FfdVersion, Receipt, Taxation};
 use tinkoff_mapi::InitPaymentAction;
 
+/// Replays a hand-written cassette: the response was not recorded
+/// from the Tinkoff sandbox, it only has the shape of a successful Init.
+#[tokio::test]
+async fn init_payment_with_synthetic_cassette() {
+    let payment = payment();
+    let cassette = Cassette::replay(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/cassettes/init_synthetic.json"
+    ))
+    .unwrap();
+    let client = tinkoff_mapi::Client::with_transport(
+        "https://securepay.tinkoff.ru/v2",
+        cassette,
+    )
+    .unwrap();
+    client.execute(InitPaymentAction, payment).await.unwrap();
+}
+
+/// Records Init through the cassette and replays the written file,
+/// the recorded request must not keep the real `Token`.
+#[tokio::test]
+async fn init_payment_is_recorded_scrubbed_and_replayed() {
+    let transport =
+        InMemoryTransport::new().route(Method::POST, "/v2/Init", |_req| {
+            let body = serde_json::json!({
+                "Success": true,
+                "ErrorCode": "0",
+                "TerminalKey": "a",
+                "Status": "NEW",
+                "PaymentId": 3093639567u64,
+                "OrderId": 1,
+                "Amount": 10,
+            });
+            HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+        });
+    let path = std::env::temp_dir()
+        .join(format!("tinkoff-init-{}.json", std::process::id()));
+    let client = tinkoff_mapi::Client::with_transport(
+        "https://securepay.tinkoff.ru/v2",
+        Cassette::record(&path, transport.clone()),
+    )
+    .unwrap();
+    let recorded = client.execute(InitPaymentAction, payment()).await.unwrap();
+
+    let sent: serde_json::Value =
+        serde_json::from_slice(&transport.requests()[0].body).unwrap();
+    let token = sent["Token"].as_str().unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    let cassette: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let body = &cassette[0]["request"]["body"]["json"];
+    assert_eq!(body["Token"], "[REDACTED]");
+    assert_eq!(body["TerminalKey"], "a");
+    assert!(!content.contains(token));
+    assert!(!content.contains("secret"));
+
+    let client = tinkoff_mapi::Client::with_transport(
+        "https://securepay.tinkoff.ru/v2",
+        Cassette::replay(&path).unwrap(),
+    )
+    .unwrap();
+    let replayed = client.execute(InitPaymentAction, payment()).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(format!("{replayed:?}"), format!("{recorded:?}"));
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn init_payment_with_in_memory_transport() {
+    let transport =
+        InMemoryTransport::new().route(Method::POST, "/v2/Init", |_req| {
+            let body = serde_json::json!({
+                "Success": true,
+                "ErrorCode": "0",
+                "TerminalKey": "a",
+                "Status": "NEW",
+                "PaymentId": 3093639567u64,
+                "OrderId": 1,
+                "Amount": 10,
+            });
+            HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+        });
+    let client = tinkoff_mapi::Client::with_transport(
+        "https://securepay.tinkoff.ru/v2/",
+        transport.clone(),
+    )
+    .unwrap();
+    client.execute(InitPaymentAction, payment()).await.unwrap();
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    let body: serde_json::Value =
+        serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["TerminalKey"], "a");
+    assert_eq!(body["Amount"], 10);
+    assert!(body["Token"].is_string());
+}
+
+#[tokio::test]
+async fn get_state_with_in_memory_transport() {
+    let transport =
+        InMemoryTransport::new().route(Method::POST, "/v2/GetState", |_req| {
+            let body = serde_json::json!({
+                "Success": true,
+                "ErrorCode": "0",
+                "Message": "OK",
+                "TerminalKey": "a",
+                "Status": "CONFIRMED",
+                "PaymentId": 3093639567u64,
+                "OrderId": "1",
+                "Amount": 10,
+            });
+            HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+        });
+    let client = tinkoff_mapi::Client::with_transport(
+        "https://securepay.tinkoff.ru/v2/",
+        transport.clone(),
+    )
+    .unwrap();
+    let password = Secret::new("secret".to_string());
+    let req = GetStateRequest::new("a", 3093639567, &password).unwrap();
+    let response = client.execute(GetStateAction, req).await.unwrap();
+    assert_eq!(response.status, Some(PaymentStatus::Confirmed));
+    assert!(response.status.is_some_and(|status| status.is_final()));
+
+    let body: serde_json::Value =
+        serde_json::from_slice(&transport.requests()[0].body).unwrap();
+    assert_eq!(body["PaymentId"], 3093639567u64);
+    assert!(body["Token"].is_string());
+}
+
+#[tokio::test]
+async fn repeated_init_with_same_order_is_sent_once() {
+    // The first Init fails with `200` and `Success: false`.
+    let calls = Arc::new(AtomicU32::new(0));
+    let transport =
+        InMemoryTransport::new().route(Method::POST, "/v2/Init", move |_req| {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                let body = serde_json::json!({
+                    "Success": false,
+                    "ErrorCode": "9999",
+                    "Message": "Внутренняя ошибка системы",
+                });
+                return HttpResponse::from_json(StatusCode::OK, &body).unwrap();
+            }
+            let body = serde_json::json!({
+                "Success": true,
+                "ErrorCode": "0",
+                "TerminalKey": "a",
+                "Status": "NEW",
+                "PaymentId": 3093639567u64,
+                "OrderId": 1,
+                "Amount": 10,
+            });
+            HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+        });
+    let client = tinkoff_mapi::Client::with_transport(
+        "https://securepay.tinkoff.ru/v2",
+        transport.clone(),
+    )
+    .unwrap()
+    .with_middleware(Idempotency::new(Duration::from_secs(60)));
+    assert!(client.execute(InitPaymentAction, payment()).await.is_err());
+    client.execute(InitPaymentAction, payment()).await.unwrap();
+    client.execute(InitPaymentAction, payment()).await.unwrap();
+    assert_eq!(transport.requests().len(), 2);
+}
+
 #[tokio::test]
-async fn abc() {
+async fn refunds_of_different_payments_share_external_id() {
+    let transport =
+        InMemoryTransport::new().route(Method::POST, "/v2/Cancel", |req| {
+            let req: serde_json::Value =
+                serde_json::from_slice(&req.body).unwrap();
+            let body = serde_json::json!({
+                "Success": true,
+                "ErrorCode": "0",
+                "TerminalKey": "a",
+                "Status": "REFUNDED",
+                "PaymentId": req["PaymentId"],
+                "OriginalAmount": 1000,
+                "NewAmount": 0,
+            });
+            HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+        });
+    let client = tinkoff_mapi::Client::with_transport(
+        "https://securepay.tinkoff.ru/v2",
+        transport.clone(),
+    )
+    .unwrap()
+    .with_middleware(Idempotency::new(Duration::from_secs(60)));
+    let password = Secret::new("secret".to_string());
+    let refund = |payment_id| {
+        CancelRequest::builder("a", payment_id)
+            .with_external_request_id("refund-1".to_string())
+            .build(&password)
+            .unwrap()
+    };
+    let first = client.execute(CancelAction, refund(1)).await.unwrap();
+    let second = client.execute(CancelAction, refund(2)).await.unwrap();
+    let repeated = client.execute(CancelAction, refund(2)).await.unwrap();
+    assert_eq!(first.payment_id, Some(1));
+    assert_eq!(second.payment_id, Some(2));
+    assert_eq!(repeated.payment_id, Some(2));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn init_payment_with_mock_server() {
+    let server = MockServer::start().await;
+    let body = serde_json::json!({
+        "Success": true,
+        "ErrorCode": "0",
+        "TerminalKey": "a",
+        "Status": "NEW",
+        "PaymentId": 3093639567u64,
+        "OrderId": 1,
+        "Amount": 10,
+    });
+    server.mock(
+        Mock::given(Method::POST, "/v2/Init")
+            .with_json_body(serde_json::json!({
+                "TerminalKey": "a",
+                "Amount": 10,
+            }))
+            .matching(|req| {
+                serde_json::from_slice::<serde_json::Value>(&req.body)
+                    .is_ok_and(|body| body["Token"].is_string())
+            })
+            .respond_with_json(StatusCode::OK, &body)
+            .expect(1),
+    );
+    let client =
+        tinkoff_mapi::Client::new(server.url().join("v2/").unwrap()).unwrap();
+    client.execute(InitPaymentAction, payment()).await.unwrap();
+    server.verify();
+}
+
+#[test]
+fn init_payment_with_blocking_client_from_plain_thread() {
+    let transport =
+        InMemoryTransport::new().route(Method::POST, "/v2/Init", |_req| {
+            let body = serde_json::json!({
+                "Success": true,
+                "ErrorCode": "0",
+                "TerminalKey": "a",
+                "Status": "NEW",
+                "PaymentId": 3093639567u64,
+                "OrderId": 1,
+                "Amount": 10,
+            });
+            HttpResponse::from_json(StatusCode::OK, &body).unwrap()
+        });
+    let client = BlockingClient::with_transport(
+        "https://securepay.tinkoff.ru/v2",
+        transport.clone(),
+    )
+    .unwrap();
+    std::thread::spawn(move || {
+        client.execute(InitPaymentAction, payment()).unwrap();
+    })
+    .join()
+    .unwrap();
+    assert_eq!(transport.requests().len(), 1);
+}
+
+fn payment() -> Payment {
     let amount = Kopeck::from_rub(Decimal::new(10, 0)).unwrap();
     let item = Item::builder(
         "abc",
@@ -42,17 +318,11 @@ async fn abc() {
         .with_email(Email::parse("ghashy@gmail.com").unwrap())
         .build()
         .unwrap();
-    let payment =
-        Payment::builder("a", amount, OrderId::I32(1), TerminalType::ECOM)
-            .with_payment_data(payment_data)
-            .with_receipt(receipt)
-            .build()
-            .unwrap();
-
-    let client =
-        tinkoff_mapi::Client::new("https://securepay.tinkoff.ru/v2").unwrap();
-    let response = client.execute(InitPaymentAction, payment).await.unwrap();
-    dbg!(response);
+    Payment::builder("a", amount, OrderId::I32(1), TerminalType::ECOM)
+        .with_payment_data(payment_data)
+        .with_receipt(receipt)
+        .build(&Secret::new("secret".to_string()))
+        .unwrap()
 }
 
 fn _init_tracing() {